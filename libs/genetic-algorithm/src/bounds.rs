@@ -0,0 +1,208 @@
+use rand::Rng;
+use rand::RngCore;
+
+use crate::*;
+
+// what happens to a gene that ends up outside of its range
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoundaryHandling {
+  #[default]
+  Clamp, // onto the nearest bound
+  Reflect, // mirrored back in off the bound it crossed
+  Wrap, // around to the opposite bound
+  Resample, // uniformly anywhere within the range
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneBounds {
+  ranges: Vec<(f64, f64)>, // one per gene, or a single one shared by every gene
+  handling: BoundaryHandling,
+}
+
+impl GeneBounds {
+  // the same range for every gene, whatever the chromosome length
+  pub fn uniform(min: f64, max: f64, handling: BoundaryHandling) -> Self {
+    Self::per_gene(vec![(min, max)], handling)
+  }
+
+  pub fn per_gene(ranges: Vec<(f64, f64)>, handling: BoundaryHandling) -> Self {
+    assert!(!ranges.is_empty());
+    assert!(ranges.iter().all(|(min, max)| min <= max));
+    Self { ranges, handling }
+  }
+
+  pub fn handling(&self) -> BoundaryHandling {
+    self.handling
+  }
+
+  pub fn range(&self, index: usize) -> (f64, f64) {
+    match self.ranges.as_slice() {
+      [range] => *range,
+      ranges => ranges[index],
+    }
+  }
+
+  pub fn contains(&self, chromosome: &Chromosome) -> bool {
+    self.violation(chromosome) == 0.0
+  }
+
+  // summed distance of every gene from its range, 0 when all are within
+  pub fn violation(&self, chromosome: &Chromosome) -> f64 {
+    self.check_len(chromosome);
+
+    chromosome
+      .iter()
+      .enumerate()
+      .map(|(index, &gene)| {
+        let (min, max) = self.range(index);
+        (min - gene).max(gene - max).max(0.0)
+      })
+      .sum()
+  }
+
+  // move every out of range gene back within its range
+  pub fn apply(&self, rng: &mut impl RngCore, chromosome: &mut Chromosome) {
+    self.check_len(chromosome);
+
+    for (index, gene) in chromosome.iter_mut().enumerate() {
+      let (min, max) = self.range(index);
+      if (min..=max).contains(gene) {
+        continue;
+      }
+
+      let width = max - min;
+      *gene = match self.handling {
+        _ if width == 0.0 => min,
+        BoundaryHandling::Clamp => (*gene).clamp(min, max),
+        BoundaryHandling::Reflect => {
+          // reflecting off both bounds repeats every two widths
+          let offset = (*gene - min).rem_euclid(2.0 * width);
+          min + if offset > width { 2.0 * width - offset } else { offset }
+        }
+        BoundaryHandling::Wrap => min + (*gene - min).rem_euclid(width),
+        BoundaryHandling::Resample => rng.gen_range(min..=max),
+      };
+    }
+  }
+
+  fn check_len(&self, chromosome: &Chromosome) {
+    assert!(self.ranges.len() == 1 || self.ranges.len() == chromosome.len());
+  }
+}
+
+impl Chromosome {
+  pub fn bound(&mut self, rng: &mut impl RngCore, bounds: &GeneBounds) {
+    bounds.apply(rng, self);
+  }
+}
+
+// keeps whatever another mutation produces within bounds
+#[derive(Clone, Debug)]
+pub struct BoundedMutation<M> {
+  mutation: M,
+  bounds: GeneBounds,
+}
+
+impl<M> BoundedMutation<M> {
+  pub fn new(mutation: M, bounds: GeneBounds) -> Self {
+    Self { mutation, bounds }
+  }
+
+  pub fn bounds(&self) -> &GeneBounds {
+    &self.bounds
+  }
+}
+
+impl<M> MutationMethod for BoundedMutation<M>
+  where M: MutationMethod, {
+
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    self.mutation.mutate(rng, child);
+    self.bounds.apply(rng, child);
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    self.mutation.adapt(history);
+  }
+}
+
+// how constraint violation, as reported by `Individual::violation`, is
+// weighed against fitness before selection
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Penalty {
+  #[default]
+  None,
+  Static { weight: f64 }, // fitness - weight * violation
+  Death, // any violation leaves no fitness at all
+}
+
+impl Penalty {
+  pub fn apply(&self, fitness: f64, violation: f64) -> f64 {
+    match *self {
+      _ if violation <= 0.0 => fitness,
+      Self::None => fitness,
+      Self::Static { weight } => fitness - weight * violation,
+      Self::Death => 0.0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::rng;
+
+  fn bounded(genes: &[f64], handling: BoundaryHandling) -> Vec<f64> {
+    let mut chromosome: Chromosome = genes.iter().copied().collect();
+    GeneBounds::uniform(-1.0, 1.0, handling).apply(&mut rng(), &mut chromosome);
+    chromosome.iter().copied().collect()
+  }
+
+  #[test]
+  fn genes_on_the_bounds_are_left_alone() {
+    for handling in [BoundaryHandling::Clamp, BoundaryHandling::Reflect, BoundaryHandling::Wrap, BoundaryHandling::Resample] {
+      assert_eq!(bounded(&[-1.0, 0.0, 1.0], handling), [-1.0, 0.0, 1.0]);
+    }
+  }
+
+  #[test]
+  fn clamp() {
+    assert_eq!(bounded(&[-1.5, 1.5, 7.0], BoundaryHandling::Clamp), [-1.0, 1.0, 1.0]);
+  }
+
+  #[test]
+  fn reflect() {
+    // 3.0 bounces off 1.0 back to -1.0, 5.0 off both bounds back to 1.0
+    assert_eq!(bounded(&[-1.5, 1.5, 3.0, 5.0], BoundaryHandling::Reflect), [-0.5, 0.5, -1.0, 1.0]);
+  }
+
+  #[test]
+  fn wrap() {
+    // 3.0 wraps a whole width onto the lower bound
+    assert_eq!(bounded(&[-1.5, 1.5, 3.0], BoundaryHandling::Wrap), [0.5, -0.5, -1.0]);
+  }
+
+  #[test]
+  fn resample_stays_within_range() {
+    let genes = bounded(&[-10.0, 10.0, 1e9], BoundaryHandling::Resample);
+    assert!(genes.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+  }
+
+  #[test]
+  fn empty_range_collapses_onto_it() {
+    let mut chromosome: Chromosome = [5.0, -5.0].into_iter().collect();
+    GeneBounds::uniform(0.5, 0.5, BoundaryHandling::Reflect).apply(&mut rng(), &mut chromosome);
+    assert!(chromosome.iter().eq([0.5, 0.5].iter()));
+  }
+
+  #[test]
+  fn violation_sums_the_distances() {
+    let chromosome: Chromosome = [-1.5, 0.0, 3.0].into_iter().collect();
+    let bounds = GeneBounds::uniform(-1.0, 1.0, BoundaryHandling::Clamp);
+    assert_eq!(bounds.violation(&chromosome), 2.5);
+    assert!(!bounds.contains(&chromosome));
+  }
+}
@@ -0,0 +1,505 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::*;
+
+pub struct GeneticAlgorithmBuilder {
+  selection_method: Box<dyn DynSelectionMethod>,
+  crossover_method: Box<dyn DynCrossoverMethod>,
+  mutation_method: Box<dyn DynMutationMethod>,
+  fitness_scaling: FitnessScaling,
+  penalty: Penalty,
+  bounds: Option<GeneBounds>,
+}
+
+impl GeneticAlgorithmBuilder {
+  // starts out with the operators of `DefaultGeneticAlgorithm`
+  pub fn new() -> Self {
+    GeneticAlgorithmConfig::default()
+      .builder()
+      .expect("Error - default config is valid")
+  }
+
+  pub fn selection_method(mut self, selection_method: impl DynSelectionMethod + 'static) -> Self {
+    self.selection_method = Box::new(selection_method);
+    self
+  }
+
+  pub fn crossover_method(mut self, crossover_method: impl DynCrossoverMethod + 'static) -> Self {
+    self.crossover_method = Box::new(crossover_method);
+    self
+  }
+
+  pub fn mutation_method(mut self, mutation_method: impl DynMutationMethod + 'static) -> Self {
+    self.mutation_method = Box::new(mutation_method);
+    self
+  }
+
+  pub fn fitness_scaling(mut self, fitness_scaling: FitnessScaling) -> Self {
+    self.fitness_scaling = fitness_scaling;
+    self
+  }
+
+  pub fn penalty(mut self, penalty: Penalty) -> Self {
+    self.penalty = penalty;
+    self
+  }
+
+  // applied after every mutation, whichever mutation method is picked
+  pub fn bounds(mut self, bounds: GeneBounds) -> Self {
+    self.bounds = Some(bounds);
+    self
+  }
+
+  pub fn build(self) -> DynGeneticAlgorithm {
+    let mutation_method = match self.bounds {
+      Some(bounds) => Box::new(BoundedMutation::new(self.mutation_method, bounds)),
+      None => self.mutation_method,
+    };
+
+    GeneticAlgorithm::new(self.selection_method, self.crossover_method, mutation_method)
+      .with_fitness_scaling(self.fitness_scaling)
+      .with_penalty(self.penalty)
+  }
+}
+
+impl Default for GeneticAlgorithmBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl DynGeneticAlgorithm {
+  pub fn builder() -> GeneticAlgorithmBuilder {
+    GeneticAlgorithmBuilder::new()
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelectionConfig {
+  RouletteWheel,
+  Tournament { size: usize },
+  Speciated { compatibility_threshold: f64, stagnation_limit: usize }, // roulette wheel within species
+  Novelty { k: usize, threshold: f64, capacity: usize, novelty_weight: f64 }, // roulette wheel by mixed score
+  Nsga2, // crowded tournaments over pareto fronts, see `OptimizerConfig::Nsga2` for the elitist version
+}
+
+// configs build genetic algorithms of real genes, so permutation operators
+// like `OrderCrossover` are left to `GeneticAlgorithmBuilder`
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CrossoverConfig {
+  Uniform,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MutationConfig {
+  Gaussian { chance: f64, mag: f64 },
+  Normal { chance: f64, sigma: f64 },
+  Cauchy { chance: f64, scale: f64 },
+  Polynomial { chance: f64, eta: f64, min: f64, max: f64 },
+  Reset { chance: f64, min: f64, max: f64 },
+  SelfAdaptive { initial_step_size: f64, min_step_size: f64 },
+}
+
+// how the rates of a gaussian, normal or cauchy mutation change as the run goes
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScheduleConfig {
+  GenerationDecay { decay: f64, floor_chance: f64, floor_mag: f64 },
+  StagnationBoost { generations: usize, boost: f64, epsilon: f64 },
+  OneFifthRule { window: usize, factor: f64 },
+}
+
+// the same range for every gene
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoundsConfig {
+  pub min: f64,
+  pub max: f64,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub handling: BoundaryHandling,
+}
+
+impl From<BoundsConfig> for GeneBounds {
+  fn from(BoundsConfig { min, max, handling }: BoundsConfig) -> Self {
+    GeneBounds::uniform(min, max, handling)
+  }
+}
+
+// operators as plain data, to be read from a config file or handed over by the web ui
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneticAlgorithmConfig {
+  pub selection: SelectionConfig,
+  pub crossover: CrossoverConfig,
+  pub mutation: MutationConfig,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub scaling: FitnessScaling,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub penalty: Penalty,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub bounds: Option<BoundsConfig>,
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub schedule: Option<ScheduleConfig>,
+}
+
+impl GeneticAlgorithmConfig {
+  // every value within what its operator accepts, so building cannot panic
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    match self.selection {
+      SelectionConfig::RouletteWheel | SelectionConfig::Nsga2 => {}
+      SelectionConfig::Tournament { size } => {
+        check(size > 0, "tournament size must be positive", size)?;
+      }
+      SelectionConfig::Speciated { compatibility_threshold, stagnation_limit } => {
+        check(compatibility_threshold > 0.0, "compatibility threshold must be positive", compatibility_threshold)?;
+        check(stagnation_limit > 0, "stagnation limit must be positive", stagnation_limit)?;
+      }
+      SelectionConfig::Novelty { k, threshold, capacity, novelty_weight } => {
+        check(k > 0, "novelty neighbours must be positive", k)?;
+        check(threshold >= 0.0, "novelty threshold must not be negative", threshold)?;
+        check(capacity > 0, "novelty archive capacity must be positive", capacity)?;
+        check((0.0..=1.0).contains(&novelty_weight), "novelty weight must be within 0..=1", novelty_weight)?;
+      }
+    }
+
+    self.mutation.validate()?;
+
+    if let Some(schedule) = self.schedule {
+      check(
+        matches!(self.mutation, MutationConfig::Gaussian { .. } | MutationConfig::Normal { .. } | MutationConfig::Cauchy { .. }),
+        "only gaussian, normal and cauchy mutations can be scheduled",
+        self.mutation,
+      )?;
+      schedule.validate()?;
+    }
+
+    match self.scaling {
+      FitnessScaling::Identity => {}
+      FitnessScaling::Linear { multiple } => check(multiple.is_finite() && multiple >= 1.0, "linear scaling multiple must be at least 1", multiple)?,
+      FitnessScaling::SigmaTruncation { c } => check(c.is_finite() && c >= 0.0, "sigma truncation c must not be negative", c)?,
+      FitnessScaling::Windowing { floor } => check(floor.is_finite() && floor >= 0.0, "windowing floor must not be negative", floor)?,
+      FitnessScaling::PowerLaw { exponent } => check(exponent.is_finite() && exponent > 0.0, "power law exponent must be positive", exponent)?,
+      FitnessScaling::Boltzmann { temperature } => check(temperature.is_finite() && temperature > 0.0, "boltzmann temperature must be positive", temperature)?,
+    }
+
+    if let Penalty::Static { weight } = self.penalty {
+      check(weight.is_finite() && weight >= 0.0, "penalty weight must not be negative", weight)?;
+    }
+
+    if let Some(BoundsConfig { min, max, .. }) = self.bounds {
+      check(min <= max, "bounds must not be empty", (min, max))?;
+    }
+
+    Ok(())
+  }
+
+  pub fn builder(&self) -> Result<GeneticAlgorithmBuilder, ConfigError> {
+    self.validate()?;
+
+    let selection_method: Box<dyn DynSelectionMethod> = match self.selection {
+      SelectionConfig::RouletteWheel => Box::new(RouletteWheelSelection::new()),
+      SelectionConfig::Tournament { size } => Box::new(TournamentSelection::new(size)),
+      SelectionConfig::Speciated { compatibility_threshold, stagnation_limit } => {
+        Box::new(SpeciatedSelection::new(RouletteWheelSelection::new(), compatibility_threshold, stagnation_limit))
+      }
+      SelectionConfig::Novelty { k, threshold, capacity, novelty_weight } => {
+        Box::new(NoveltySelection::new(
+          RouletteWheelSelection::new(),
+          NoveltyObjective::Mixed { novelty_weight },
+          NoveltyArchive::new(k, threshold, capacity),
+        ))
+      }
+      SelectionConfig::Nsga2 => Box::new(Nsga2Selection::new()),
+    };
+
+    Ok(GeneticAlgorithmBuilder {
+      selection_method,
+      crossover_method: self.crossover.build(),
+      mutation_method: match self.schedule {
+        Some(schedule) => self.mutation.scheduled(schedule.build())?,
+        None => self.mutation.build()?,
+      },
+      fitness_scaling: self.scaling,
+      penalty: self.penalty,
+      bounds: self.bounds.map(GeneBounds::from),
+    })
+  }
+
+  pub fn build(&self) -> Result<DynGeneticAlgorithm, ConfigError> {
+    Ok(self.builder()?.build())
+  }
+}
+
+impl CrossoverConfig {
+  pub fn build(&self) -> Box<dyn DynCrossoverMethod> {
+    match self {
+      Self::Uniform => Box::new(UniformCrossover::new()),
+    }
+  }
+}
+
+impl ScheduleConfig {
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    match *self {
+      Self::GenerationDecay { decay, floor_chance, floor_mag } => {
+        check((0.0..=1.0).contains(&decay), "decay must be within 0..=1", decay)?;
+        check((0.0..=1.0).contains(&floor_chance), "floor chance must be within 0..=1", floor_chance)?;
+        check(floor_mag.is_finite() && floor_mag >= 0.0, "floor magnitude must not be negative", floor_mag)
+      }
+      Self::StagnationBoost { generations, boost, epsilon } => {
+        check(generations > 0, "stagnant generations must be positive", generations)?;
+        check(boost.is_finite() && boost >= 1.0, "boost must be at least 1", boost)?;
+        check(epsilon.is_finite() && epsilon >= 0.0, "epsilon must not be negative", epsilon)
+      }
+      Self::OneFifthRule { window, factor } => {
+        check(window > 0, "window must be positive", window)?;
+        check(factor > 0.0 && factor < 1.0, "factor must be within 0..1", factor)
+      }
+    }
+  }
+
+  fn build(&self) -> Box<dyn MutationSchedule + Send + Sync> {
+    match *self {
+      Self::GenerationDecay { decay, floor_chance, floor_mag } => {
+        Box::new(GenerationDecay::new(decay, MutationRates::new(floor_chance, floor_mag)))
+      }
+      Self::StagnationBoost { generations, boost, epsilon } => Box::new(StagnationBoost::new(generations, boost, epsilon)),
+      Self::OneFifthRule { window, factor } => Box::new(OneFifthRule::new(window, factor)),
+    }
+  }
+}
+
+impl MutationConfig {
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    match *self {
+      Self::Gaussian { chance, mag } => {
+        check_chance(chance)?;
+        check(mag.is_finite() && mag >= 0.0, "mutation magnitude must not be negative", mag)
+      }
+      Self::Normal { chance, sigma } => {
+        check_chance(chance)?;
+        check(sigma.is_finite() && sigma >= 0.0, "mutation sigma must not be negative", sigma)
+      }
+      Self::Cauchy { chance, scale } => {
+        check_chance(chance)?;
+        check(scale.is_finite() && scale > 0.0, "mutation scale must be positive", scale)
+      }
+      Self::Polynomial { chance, eta, min, max } => {
+        check_chance(chance)?;
+        check(eta.is_finite() && eta >= 0.0, "distribution index must not be negative", eta)?;
+        check(min < max, "mutation range must not be empty", (min, max))
+      }
+      Self::Reset { chance, min, max } => {
+        check_chance(chance)?;
+        check(min <= max, "mutation range must not be empty", (min, max))
+      }
+      Self::SelfAdaptive { initial_step_size, min_step_size } => {
+        check(initial_step_size.is_finite() && initial_step_size > 0.0, "initial step size must be positive", initial_step_size)?;
+        check(min_step_size.is_finite() && min_step_size >= 0.0, "min step size must not be negative", min_step_size)
+      }
+    }
+  }
+
+  pub fn build(&self) -> Result<Box<dyn DynMutationMethod>, ConfigError> {
+    self.validate()?;
+
+    Ok(match *self {
+      Self::Gaussian { chance, mag } => Box::new(GaussianMutation::new(chance, mag)),
+      Self::Normal { chance, sigma } => Box::new(NormalMutation::new(chance, sigma)),
+      Self::Cauchy { chance, scale } => Box::new(CauchyMutation::new(chance, scale)),
+      Self::Polynomial { chance, eta, min, max } => Box::new(PolynomialMutation::new(chance, eta, min, max)),
+      Self::Reset { chance, min, max } => Box::new(ResetMutation::new(chance, min, max)),
+      Self::SelfAdaptive { initial_step_size, min_step_size } => {
+        Box::new(SelfAdaptiveMutation::new(initial_step_size, min_step_size))
+      }
+    })
+  }
+
+  fn scheduled(&self, schedule: Box<dyn MutationSchedule + Send + Sync>) -> Result<Box<dyn DynMutationMethod>, ConfigError> {
+    self.validate()?;
+
+    Ok(match *self {
+      Self::Gaussian { chance, mag } => Box::new(ScheduledMutation::new(GaussianMutation::new(chance, mag), schedule)),
+      Self::Normal { chance, sigma } => Box::new(ScheduledMutation::new(NormalMutation::new(chance, sigma), schedule)),
+      Self::Cauchy { chance, scale } => Box::new(ScheduledMutation::new(CauchyMutation::new(chance, scale), schedule)),
+      _ => return Err(ConfigError(format!("{self:?} cannot be scheduled"))),
+    })
+  }
+}
+
+impl Default for GeneticAlgorithmConfig {
+  fn default() -> Self {
+    Self {
+      selection: SelectionConfig::RouletteWheel,
+      crossover: CrossoverConfig::Uniform,
+      mutation: MutationConfig::Gaussian { chance: 0.015, mag: 0.3 },
+      scaling: FitnessScaling::Identity,
+      penalty: Penalty::None,
+      bounds: None,
+      schedule: None,
+    }
+  }
+}
+
+// any optimizer as plain data, the genetic algorithm by default
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OptimizerConfig {
+  GeneticAlgorithm(GeneticAlgorithmConfig),
+  CmaEs { sigma: f64 },
+  DifferentialEvolution { variant: DifferentialVariant, weight: f64, crossover_rate: f64 },
+  EvolutionStrategy { mu: usize, replacement: Replacement, mutation: MutationConfig },
+  Nsga2 { crossover: CrossoverConfig, mutation: MutationConfig },
+}
+
+impl OptimizerConfig {
+  pub fn validate(&self) -> Result<(), ConfigError> {
+    match *self {
+      Self::GeneticAlgorithm(config) => config.validate(),
+      Self::CmaEs { sigma } => check(sigma.is_finite() && sigma > 0.0, "cma-es sigma must be positive", sigma),
+      Self::DifferentialEvolution { weight, crossover_rate, .. } => {
+        check((0.0..=2.0).contains(&weight), "differential weight must be within 0..=2", weight)?;
+        check((0.0..=1.0).contains(&crossover_rate), "crossover rate must be within 0..=1", crossover_rate)
+      }
+      Self::EvolutionStrategy { mu, mutation, .. } => {
+        check(mu > 0, "mu must be positive", mu)?;
+        mutation.validate()
+      }
+      Self::Nsga2 { mutation, .. } => mutation.validate(),
+    }
+  }
+
+  pub fn build(&self) -> Result<DynOptimizer, ConfigError> {
+    self.validate()?;
+
+    Ok(match *self {
+      Self::GeneticAlgorithm(config) => DynOptimizer::GeneticAlgorithm(config.build()?),
+      Self::CmaEs { sigma } => DynOptimizer::CmaEs(CmaEs::new(sigma)),
+      Self::DifferentialEvolution { variant, weight, crossover_rate } => {
+        DynOptimizer::DifferentialEvolution(DifferentialEvolution::new(variant, weight, crossover_rate))
+      }
+      Self::EvolutionStrategy { mu, replacement, mutation } => {
+        DynOptimizer::EvolutionStrategy(EvolutionStrategy::new(mu, replacement, mutation.build()?))
+      }
+      Self::Nsga2 { crossover, mutation } => DynOptimizer::Nsga2(Nsga2::new(crossover.build(), mutation.build()?)),
+    })
+  }
+}
+
+impl Default for OptimizerConfig {
+  fn default() -> Self {
+    Self::GeneticAlgorithm(GeneticAlgorithmConfig::default())
+  }
+}
+
+impl From<GeneticAlgorithmConfig> for OptimizerConfig {
+  fn from(config: GeneticAlgorithmConfig) -> Self {
+    Self::GeneticAlgorithm(config)
+  }
+}
+
+// a config value its operator would reject
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError(String);
+
+impl fmt::Display for ConfigError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+impl Error for ConfigError {}
+
+fn check(valid: bool, message: &str, value: impl fmt::Debug) -> Result<(), ConfigError> {
+  if valid {
+    Ok(())
+  } else {
+    Err(ConfigError(format!("{message}, got {value:?}")))
+  }
+}
+
+fn check_chance(chance: f64) -> Result<(), ConfigError> {
+  check((0.0..=1.0).contains(&chance), "mutation chance must be within 0..=1", chance)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn with_mutation(mutation: MutationConfig) -> GeneticAlgorithmConfig {
+    GeneticAlgorithmConfig {
+      mutation,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn default_is_valid() {
+    assert!(GeneticAlgorithmConfig::default().build().is_ok());
+  }
+
+  #[test]
+  fn rejects_chance_above_one() {
+    let config = with_mutation(MutationConfig::Gaussian { chance: 1.5, mag: 0.3 });
+    assert!(config.build().is_err());
+  }
+
+  #[test]
+  fn rejects_negative_magnitude() {
+    let config = with_mutation(MutationConfig::Gaussian { chance: 0.1, mag: -0.3 });
+    assert!(config.build().is_err());
+  }
+
+  #[test]
+  fn rejects_empty_ranges() {
+    let config = with_mutation(MutationConfig::Reset { chance: 0.1, min: 1.0, max: -1.0 });
+    assert!(config.build().is_err());
+
+    let config = GeneticAlgorithmConfig {
+      bounds: Some(BoundsConfig { min: 1.0, max: -1.0, handling: BoundaryHandling::Clamp }),
+      ..Default::default()
+    };
+    assert!(config.build().is_err());
+  }
+
+  #[test]
+  fn rejects_empty_tournaments() {
+    let config = GeneticAlgorithmConfig {
+      selection: SelectionConfig::Tournament { size: 0 },
+      ..Default::default()
+    };
+    assert!(config.build().is_err());
+  }
+
+  #[test]
+  fn tournaments_pick_the_best_entrant() {
+    let population = [
+      crate::testing::TestIndividual::new(&[0.0], 1.0),
+      crate::testing::TestIndividual::new(&[1.0], 2.0),
+    ];
+    let mut rng = crate::testing::rng();
+
+    // with 64 entrants, the fitter individual is all but certain to enter
+    for _ in 0..10 {
+      assert_eq!(TournamentSelection::new(64).select(&mut rng, &population).fitness, 2.0);
+    }
+  }
+
+  #[test]
+  fn schedules_only_adaptive_mutations() {
+    let schedule = Some(ScheduleConfig::StagnationBoost { generations: 10, boost: 2.0, epsilon: 0.0 });
+
+    let config = GeneticAlgorithmConfig { schedule, ..Default::default() };
+    assert!(config.build().is_ok());
+
+    let config = GeneticAlgorithmConfig {
+      schedule,
+      ..with_mutation(MutationConfig::Reset { chance: 0.1, min: -1.0, max: 1.0 })
+    };
+    assert!(config.build().is_err());
+  }
+}
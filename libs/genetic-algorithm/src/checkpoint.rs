@@ -0,0 +1,144 @@
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+
+use std::io;
+use std::fs::File;
+use std::path::Path;
+use std::io::{BufReader, BufWriter};
+
+use crate::*;
+
+// bumped whenever the layout below changes, older files are refused
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+// everything needed to resume a run: the evaluated population, the generation
+// it belongs to, all statistics so far and the random number generator, plus
+// whatever else the caller has to restore (flight state, optimizer state...)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint<R, E = ()> {
+  version: u32,
+  pub generation: usize,
+  pub chromosomes: Vec<Chromosome>,
+  pub fitnesses: Vec<f64>,
+  pub history: Vec<Statistics>,
+  pub rng: R,
+  pub extra: E,
+}
+
+impl<R, E> Checkpoint<R, E>
+  where R: Serialize + DeserializeOwned,
+        E: Serialize + DeserializeOwned, {
+
+  pub fn new<I>(generation: usize, population: &[I], history: &[Statistics], rng: R, extra: E) -> Self
+    where I: Individual<Gene = f64> {
+    Self {
+      version: CHECKPOINT_VERSION,
+      generation,
+      chromosomes: population.iter().map(|individual| individual.as_ref().clone()).collect(),
+      fitnesses: population.iter().map(|individual| individual.fitness()).collect(),
+      history: history.to_vec(),
+      rng,
+      extra,
+    }
+  }
+
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(writer, self).map_err(io::Error::from)
+  }
+
+  pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+    let reader = BufReader::new(File::open(path)?);
+    let checkpoint: Self = serde_json::from_reader(reader).map_err(io::Error::from)?;
+
+    if checkpoint.version != CHECKPOINT_VERSION {
+      return Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("checkpoint version {} is not {}", checkpoint.version, CHECKPOINT_VERSION),
+      ));
+    }
+
+    if checkpoint.chromosomes.len() != checkpoint.fitnesses.len() {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "checkpoint population is truncated"));
+    }
+
+    Ok(checkpoint)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::TestIndividual;
+
+  use std::env;
+  use std::fs;
+
+  fn population() -> Vec<TestIndividual> {
+    (0..6)
+      .map(|i| TestIndividual::new(&[i as f64 * 0.1, 1.0 / 3.0], i as f64 + 0.5))
+      .collect()
+  }
+
+  #[test]
+  fn round_trips_bit_for_bit() {
+    let population = population();
+    let history = [Statistics::new(&population)];
+    let path = env::temp_dir().join(format!("ga-checkpoint-{}.json", std::process::id()));
+
+    Checkpoint::new(3, &population, &history, 7_u64, "extra".to_string()).save(&path).unwrap();
+    let checkpoint: Checkpoint<u64, String> = Checkpoint::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!((checkpoint.generation, checkpoint.rng, checkpoint.extra.as_str()), (3, 7, "extra"));
+    assert_eq!(checkpoint.fitnesses, population.iter().map(|individual| individual.fitness).collect::<Vec<_>>());
+    for (chromosome, individual) in checkpoint.chromosomes.iter().zip(&population) {
+      assert!(chromosome.iter().eq(individual.chromosome.iter()));
+    }
+    assert_eq!(checkpoint.history[0].avg_fitness(), history[0].avg_fitness());
+  }
+
+  #[test]
+  fn rejects_other_versions() {
+    let path = env::temp_dir().join(format!("ga-checkpoint-version-{}.json", std::process::id()));
+    let mut checkpoint = Checkpoint::new(0, &population(), &[], (), ());
+    checkpoint.version = CHECKPOINT_VERSION + 1;
+    checkpoint.save(&path).unwrap();
+
+    let error = Checkpoint::<(), ()>::load(&path).unwrap_err();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+  }
+
+  #[test]
+  fn optimizer_state_round_trips() {
+    let mut rng = crate::testing::rng();
+    let mut de = DifferentialEvolution::new(DifferentialVariant::Rand1Bin, 0.5, 0.9);
+    de.next_generation(&mut rng, &population());
+
+    let mut resumed = DifferentialEvolution::new(DifferentialVariant::Rand1Bin, 0.5, 0.9);
+    resumed.load_state(de.save_state()).unwrap();
+
+    let (trials, _): (Vec<TestIndividual>, _) = de.next_generation(&mut rng.clone(), &population());
+    let (resumed_trials, _): (Vec<TestIndividual>, _) = resumed.next_generation(&mut rng, &population());
+    for (trial, resumed_trial) in trials.iter().zip(&resumed_trials) {
+      assert!(trial.chromosome.iter().eq(resumed_trial.chromosome.iter()));
+    }
+  }
+
+  #[test]
+  fn cma_es_rejects_mismatched_state() {
+    let mut rng = crate::testing::rng();
+    let mut cma_es = CmaEs::new(0.5);
+    cma_es.next_generation::<TestIndividual>(&mut rng, &population());
+
+    let mut state = cma_es.save_state();
+    state["covariance"].as_array_mut().unwrap().pop();
+
+    assert!(CmaEs::new(0.5).load_state(state).is_err());
+  }
+}
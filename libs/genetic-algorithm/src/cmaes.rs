@@ -0,0 +1,314 @@
+use nalgebra::{DMatrix, DVector};
+use rand_distr::StandardNormal;
+
+use crate::*;
+
+// covariance matrix adaptation evolution strategy (hansen), maximizing fitness;
+// every population handed in must be the evaluated samples of the previous
+// generation, except for the first one which only seeds the mean
+#[derive(Clone, Debug)]
+pub struct CmaEs {
+  initial_sigma: f64,
+  state: Option<CmaEsState>,
+}
+
+#[derive(Clone, Debug)]
+struct CmaEsState {
+  mean: DVector<f64>,
+  sigma: f64,
+  covariance: DMatrix<f64>,
+  path_sigma: DVector<f64>,
+  path_c: DVector<f64>,
+  basis: DMatrix<f64>, // eigenvectors of the covariance
+  scales: DVector<f64>, // square roots of its eigenvalues
+  generation: usize,
+}
+
+impl CmaEs {
+  pub fn new(initial_sigma: f64) -> Self {
+    assert!(initial_sigma > 0.0);
+
+    Self {
+      initial_sigma,
+      state: None,
+    }
+  }
+
+  pub fn sigma(&self) -> Option<f64> {
+    self.state.as_ref().map(|state| state.sigma)
+  }
+
+  pub fn mean(&self) -> Option<Chromosome> {
+    self.state.as_ref().map(|state| state.mean.iter().copied().collect())
+  }
+
+  fn update<I>(&mut self, population: &[I])
+    where I: Individual<Gene = f64> {
+    let n = population[0].as_ref().len();
+    let lambda = population.len();
+    let mu = (lambda / 2).max(1);
+
+    // best first
+    let mut order: Vec<_> = (0..lambda).collect();
+    order.sort_by(|&a, &b| population[b].fitness().total_cmp(&population[a].fitness()));
+
+    let weights: Vec<_> = (0..mu)
+      .map(|i| (mu as f64 + 0.5).ln() - (i as f64 + 1.0).ln())
+      .collect();
+    let weight_sum = weights.iter().sum::<f64>();
+    let weights: Vec<_> = weights.iter().map(|w| w / weight_sum).collect();
+    let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+    let genes = |index: usize| DVector::from_iterator(n, population[index].as_ref().iter().copied());
+
+    let recombined = order
+      .iter()
+      .zip(&weights)
+      .fold(DVector::zeros(n), |mean, (&index, w)| mean + genes(index) * *w);
+
+    let initial_sigma = self.initial_sigma;
+    let state = match &mut self.state {
+      Some(state) if state.mean.len() == n => state,
+      state => {
+        *state = Some(CmaEsState {
+          mean: recombined,
+          sigma: initial_sigma,
+          covariance: DMatrix::identity(n, n),
+          path_sigma: DVector::zeros(n),
+          path_c: DVector::zeros(n),
+          basis: DMatrix::identity(n, n),
+          scales: DVector::from_element(n, 1.0),
+          generation: 0,
+        });
+        return;
+      }
+    };
+
+    let nf = n as f64;
+    let c_sigma = (mu_eff + 2.0) / (nf + mu_eff + 5.0);
+    let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+    let c_c = (4.0 + mu_eff / nf) / (nf + 4.0 + 2.0 * mu_eff / nf);
+    let c_1 = 2.0 / ((nf + 1.3).powi(2) + mu_eff);
+    let c_mu = (1.0 - c_1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((nf + 2.0).powi(2) + mu_eff));
+    let expected_norm = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+    // steps of the selected samples, in units of sigma
+    let steps: Vec<_> = order
+      .iter()
+      .take(mu)
+      .map(|&index| (genes(index) - &state.mean) / state.sigma)
+      .collect();
+    let step = steps
+      .iter()
+      .zip(&weights)
+      .fold(DVector::zeros(n), |step, (y, w)| step + y * *w);
+
+    state.mean += &step * state.sigma;
+
+    // evolution paths
+    let inv_sqrt = &state.basis
+      * DMatrix::from_diagonal(&state.scales.map(|d| 1.0 / d))
+      * state.basis.transpose();
+
+    state.path_sigma = &state.path_sigma * (1.0 - c_sigma)
+      + inv_sqrt * &step * (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt();
+
+    state.generation += 1;
+    let norm = state.path_sigma.norm() / (1.0 - (1.0 - c_sigma).powi(2 * state.generation as i32)).sqrt();
+    let h_sigma = if norm < (1.4 + 2.0 / (nf + 1.0)) * expected_norm { 1.0 } else { 0.0 };
+
+    state.path_c = &state.path_c * (1.0 - c_c)
+      + &step * (h_sigma * (c_c * (2.0 - c_c) * mu_eff).sqrt());
+
+    // covariance
+    let rank_one = &state.path_c * state.path_c.transpose()
+      + &state.covariance * ((1.0 - h_sigma) * c_c * (2.0 - c_c));
+    let rank_mu = steps
+      .iter()
+      .zip(&weights)
+      .fold(DMatrix::zeros(n, n), |rank_mu, (y, w)| rank_mu + y * y.transpose() * *w);
+
+    state.covariance = &state.covariance * (1.0 - c_1 - c_mu) + rank_one * c_1 + rank_mu * c_mu;
+
+    // step size
+    state.sigma *= ((c_sigma / d_sigma) * (state.path_sigma.norm() / expected_norm - 1.0)).exp();
+
+    // keep the covariance symmetric against rounding before decomposing it
+    state.covariance = (&state.covariance + state.covariance.transpose()) * 0.5;
+    let eigen = state.covariance.clone().symmetric_eigen();
+    state.basis = eigen.eigenvectors;
+    state.scales = eigen.eigenvalues.map(|e| e.max(1e-20).sqrt());
+  }
+
+  fn sample<I>(&self, rng: &mut impl RngCore, lambda: usize) -> Vec<I>
+    where I: Individual<Gene = f64> {
+    let state = self.state.as_ref().expect("Error - cma-es was never updated");
+    let n = state.mean.len();
+
+    (0..lambda)
+      .map(|_| {
+        let z = DVector::from_fn(n, |_, _| rng.sample::<f64, _>(StandardNormal));
+        let y = &state.basis * z.component_mul(&state.scales);
+        let x = &state.mean + y * state.sigma;
+
+        I::from(x.iter().copied().collect())
+      })
+      .collect()
+  }
+}
+
+impl Optimizer for CmaEs {
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = f64> {
+    assert!(!population.is_empty());
+
+    self.update(population);
+
+    let new_population = self.sample(rng, population.len());
+    let stats = Statistics::new(population);
+
+    (new_population, stats)
+  }
+
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    save(&self.state.as_ref().map(CmaEsSnapshot::from))
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    let snapshot: Option<CmaEsSnapshot> = load(state)?;
+    self.state = snapshot.map(CmaEsState::try_from).transpose()?;
+    Ok(())
+  }
+}
+
+// the state as plain numbers, matrices column by column
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CmaEsSnapshot {
+  mean: Vec<f64>,
+  sigma: f64,
+  covariance: Vec<f64>,
+  path_sigma: Vec<f64>,
+  path_c: Vec<f64>,
+  basis: Vec<f64>,
+  scales: Vec<f64>,
+  generation: usize,
+}
+
+#[cfg(feature = "serde")]
+impl From<&CmaEsState> for CmaEsSnapshot {
+  fn from(state: &CmaEsState) -> Self {
+    Self {
+      mean: state.mean.as_slice().to_vec(),
+      sigma: state.sigma,
+      covariance: state.covariance.as_slice().to_vec(),
+      path_sigma: state.path_sigma.as_slice().to_vec(),
+      path_c: state.path_c.as_slice().to_vec(),
+      basis: state.basis.as_slice().to_vec(),
+      scales: state.scales.as_slice().to_vec(),
+      generation: state.generation,
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CmaEsSnapshot> for CmaEsState {
+  type Error = std::io::Error;
+
+  fn try_from(snapshot: CmaEsSnapshot) -> Result<Self, Self::Error> {
+    let n = snapshot.mean.len();
+    let vectors = [&snapshot.path_sigma, &snapshot.path_c, &snapshot.scales];
+    let matrices = [&snapshot.covariance, &snapshot.basis];
+
+    if vectors.iter().any(|vector| vector.len() != n) || matrices.iter().any(|matrix| matrix.len() != n * n) {
+      return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "cma-es state has mismatched dimensions"));
+    }
+
+    Ok(Self {
+      mean: DVector::from_vec(snapshot.mean),
+      sigma: snapshot.sigma,
+      covariance: DMatrix::from_vec(n, n, snapshot.covariance),
+      path_sigma: DVector::from_vec(snapshot.path_sigma),
+      path_c: DVector::from_vec(snapshot.path_c),
+      basis: DMatrix::from_vec(n, n, snapshot.basis),
+      scales: DVector::from_vec(snapshot.scales),
+      generation: snapshot.generation,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{rng, TestIndividual};
+
+  fn evaluated(population: Vec<TestIndividual>, fitness: impl Fn(&[f64]) -> f64) -> Vec<TestIndividual> {
+    population
+      .into_iter()
+      .map(|mut individual| {
+        let genes: Vec<_> = individual.chromosome.iter().copied().collect();
+        individual.fitness = fitness(&genes);
+        individual
+      })
+      .collect()
+  }
+
+  #[test]
+  fn first_update_recombines_the_better_half() {
+    let population = [
+      TestIndividual::new(&[0.0, 0.0], 1.0),
+      TestIndividual::new(&[4.0, 2.0], 4.0),
+      TestIndividual::new(&[2.0, 2.0], 3.0),
+      TestIndividual::new(&[9.0, 9.0], 2.0),
+    ];
+    let mut cma_es = CmaEs::new(0.3);
+    cma_es.update(&population);
+
+    // log weights of the best two, ln(2.5) - ln(1) and ln(2.5) - ln(2)
+    let w = 2.5f64.ln() / (2.0 * 2.5f64.ln() - 2.0f64.ln());
+    let mean: Vec<_> = cma_es.mean().unwrap().iter().copied().collect();
+
+    assert!((mean[0] - (4.0 * w + 2.0 * (1.0 - w))).abs() < 1e-12);
+    assert!((mean[1] - 2.0).abs() < 1e-12);
+    assert_eq!(cma_es.sigma(), Some(0.3));
+  }
+
+  #[test]
+  fn converges_on_a_sphere() {
+    let sphere = |genes: &[f64]| -genes.iter().map(|gene| (gene - 3.0).powi(2)).sum::<f64>();
+    let mut rng = rng();
+    let mut cma_es = CmaEs::new(1.0);
+    let mut population = evaluated(vec![TestIndividual::new(&[0.0; 4], 0.0); 12], sphere);
+
+    for _ in 0..150 {
+      let (children, _) = cma_es.next_generation(&mut rng, &population);
+      population = evaluated(children, sphere);
+    }
+
+    let state = cma_es.state.as_ref().unwrap();
+    assert!(cma_es.mean().unwrap().iter().all(|gene| (gene - 3.0).abs() < 1e-3));
+    assert!(state.sigma < 1e-2);
+    assert!((&state.covariance - state.covariance.transpose()).norm() < 1e-12);
+  }
+
+  #[test]
+  fn restarts_when_the_dimension_changes() {
+    let mut cma_es = CmaEs::new(0.5);
+    cma_es.update(&[TestIndividual::new(&[1.0, 1.0], 1.0)]);
+    cma_es.update(&[TestIndividual::new(&[1.0, 1.0, 1.0], 1.0)]);
+
+    assert_eq!(cma_es.mean().unwrap().len(), 3);
+    assert_eq!(cma_es.state.unwrap().generation, 0);
+  }
+
+  #[test]
+  fn samples_as_many_as_it_was_handed() {
+    let population = vec![TestIndividual::new(&[1.0, 2.0, 3.0], 1.0); 5];
+    let (children, _) = CmaEs::new(0.5).next_generation(&mut rng(), &population);
+
+    assert_eq!(children.len(), 5);
+    assert!(children.iter().all(|child: &TestIndividual| child.chromosome.len() == 3));
+  }
+}
@@ -0,0 +1,186 @@
+use rand::RngCore;
+use rand::seq::SliceRandom;
+
+use crate::*;
+
+// competitive coevolution of two populations, each bred by its own optimizer:
+// hosts are scored by the parasites they meet and parasites by the hosts
+pub struct Coevolution<A, B> {
+  hosts: A,
+  parasites: B,
+  samples: usize, // parasites every host meets per generation
+}
+
+impl<A, B> Coevolution<A, B> {
+  pub fn new(hosts: A, parasites: B, samples: usize) -> Self {
+    assert!(samples > 0);
+    Self { hosts, parasites, samples }
+  }
+
+  pub fn hosts(&self) -> &A {
+    &self.hosts
+  }
+
+  pub fn parasites(&self) -> &B {
+    &self.parasites
+  }
+
+  pub fn samples(&self) -> usize {
+    self.samples
+  }
+
+  // indices of the hosts every parasite meets this generation; every host
+  // meets `samples` parasites in a row, dealt round the shuffled parasites
+  // where the previous host left off, so no parasite meets more than one host
+  // more than any other, and no host meets a parasite twice unless there are
+  // fewer parasites than samples
+  pub fn encounters(&self, rng: &mut impl RngCore, hosts: usize, parasites: usize) -> Vec<Vec<usize>> {
+    assert!(parasites > 0);
+    assert!(hosts * self.samples >= parasites, "Error - some parasites would meet no host");
+
+    let mut encounters = vec![Vec::new(); parasites];
+
+    let mut hosts: Vec<_> = (0..hosts).collect();
+    hosts.shuffle(rng);
+    let mut seats: Vec<_> = (0..parasites).collect();
+    seats.shuffle(rng);
+
+    let deals = hosts
+      .iter()
+      .flat_map(|&host| std::iter::repeat_n(host, self.samples));
+
+    for (deal, host) in deals.enumerate() {
+      encounters[seats[deal % parasites]].push(host);
+    }
+
+    encounters
+  }
+
+  // fitnesses of both populations, `play` scoring a parasite against all the
+  // hosts it meets at once and returning its own score and one per host;
+  // a host's fitness is its mean score over every parasite it met
+  pub fn evaluate<H, P, F>(&self, rng: &mut impl RngCore, hosts: &[H], parasites: &[P], mut play: F) -> (Vec<f64>, Vec<f64>)
+    where F: FnMut(&P, &[&H]) -> (f64, Vec<f64>), {
+    let mut host_scores = vec![0.0; hosts.len()];
+    let mut parasite_scores = Vec::with_capacity(parasites.len());
+
+    for (parasite, met) in parasites.iter().zip(self.encounters(rng, hosts.len(), parasites.len())) {
+      let opponents: Vec<_> = met
+        .iter()
+        .map(|&host| &hosts[host])
+        .collect();
+
+      let (parasite_score, scores) = play(parasite, &opponents);
+      assert_eq!(scores.len(), met.len());
+
+      for (&host, score) in met.iter().zip(scores) {
+        host_scores[host] += score / self.samples as f64;
+      }
+
+      parasite_scores.push(parasite_score);
+    }
+
+    (host_scores, parasite_scores)
+  }
+
+  // breed both evaluated populations, each by its own optimizer
+  pub fn next_generation<H, P>(&mut self, rng: &mut impl RngCore, hosts: &[H], parasites: &[P]) -> ((Vec<H>, Statistics), (Vec<P>, Statistics))
+    where H: Individual,
+          P: Individual,
+          A: Optimizer<H::Gene>,
+          B: Optimizer<P::Gene>, {
+    let hosts = self.hosts.next_generation(rng, hosts);
+    let parasites = self.parasites.next_generation(rng, parasites);
+
+    (hosts, parasites)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{rng, TestIndividual};
+
+  fn coevolution(samples: usize) -> Coevolution<CmaEs, CmaEs> {
+    Coevolution::new(CmaEs::new(0.5), CmaEs::new(0.5), samples)
+  }
+
+  #[test]
+  fn every_host_meets_samples_parasites() {
+    let encounters = coevolution(3).encounters(&mut rng(), 10, 4);
+
+    assert_eq!(encounters.len(), 4);
+    for host in 0..10 {
+      let met = encounters.iter().flatten().filter(|&&met| met == host).count();
+      assert_eq!(met, 3);
+    }
+
+    // thirty encounters dealt to four parasites
+    let mut dealt: Vec<_> = encounters.iter().map(Vec::len).collect();
+    dealt.sort();
+    assert_eq!(dealt, [7, 7, 8, 8]);
+  }
+
+  #[test]
+  fn hosts_meet_different_parasites() {
+    let mut rng = rng();
+
+    for (hosts, parasites, samples) in [(10, 4, 3), (7, 7, 7), (3, 5, 2), (20, 6, 1)] {
+      let encounters = coevolution(samples).encounters(&mut rng, hosts, parasites);
+
+      for met in &encounters {
+        let mut unique = met.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), met.len());
+      }
+
+      let dealt: Vec<_> = encounters.iter().map(Vec::len).collect();
+      assert!(dealt.iter().max().unwrap() - dealt.iter().min().unwrap() <= 1);
+      assert!(dealt.iter().all(|&dealt| dealt > 0));
+    }
+  }
+
+  #[test]
+  fn luckier_parasites_change_from_generation_to_generation() {
+    let mut rng = rng();
+    let coevolution = coevolution(3);
+
+    let busiest = |encounters: Vec<Vec<usize>>| -> Vec<usize> {
+      (0..encounters.len()).filter(|&i| encounters[i].len() == 8).collect()
+    };
+
+    let first = busiest(coevolution.encounters(&mut rng, 10, 4));
+    assert!((0..20).any(|_| busiest(coevolution.encounters(&mut rng, 10, 4)) != first));
+  }
+
+  #[test]
+  fn hosts_average_their_scores_over_every_parasite() {
+    let hosts = [10.0, 20.0, 30.0];
+    let parasites = [1.0, 2.0];
+
+    // hosts score their value times the parasite's, parasites the number they met
+    let (host_scores, parasite_scores) = coevolution(2).evaluate(&mut rng(), &hosts, &parasites, |parasite, met| {
+      (met.len() as f64, met.iter().map(|&&host| host * parasite).collect())
+    });
+
+    assert_eq!(parasite_scores.iter().sum::<f64>(), 6.0);
+    for (host, score) in hosts.iter().zip(host_scores) {
+      // met one parasite twice or each once: a mean of host, 1.5 * host or 2 * host
+      assert!([1.0, 1.5, 2.0].iter().any(|factor| (score - host * factor).abs() < 1e-12));
+    }
+  }
+
+  #[test]
+  fn breeds_both_populations() {
+    let hosts = vec![TestIndividual::new(&[1.0, 2.0], 1.0); 4];
+    let parasites = vec![TestIndividual::new(&[3.0], 2.0); 2];
+
+    let ((hosts, host_stats), (parasites, parasite_stats)) = coevolution(1).next_generation(&mut rng(), &hosts, &parasites);
+
+    assert_eq!((hosts.len(), parasites.len()), (4, 2));
+    assert!(hosts.iter().all(|host: &TestIndividual| host.chromosome.len() == 2));
+    assert!(parasites.iter().all(|parasite: &TestIndividual| parasite.chromosome.len() == 1));
+    assert_eq!((host_stats.max_fitness(), parasite_stats.max_fitness()), (1.0, 2.0));
+  }
+}
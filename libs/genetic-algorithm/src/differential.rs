@@ -0,0 +1,168 @@
+use rand::Rng;
+
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DifferentialVariant {
+  Rand1Bin, // random base vector
+  Best1Bin, // best target as base vector
+}
+
+// storn and price's differential evolution; every population handed in after
+// the first must be the evaluated trials of the previous generation, and each
+// trial replaces its target when it scores at least as well
+#[derive(Clone, Debug)]
+pub struct DifferentialEvolution {
+  variant: DifferentialVariant,
+  weight: f64, // differential weight F
+  crossover_rate: f64, // chance of taking a gene from the mutant, CR
+  targets: Vec<Evaluated>,
+}
+
+impl DifferentialEvolution {
+  pub fn new(variant: DifferentialVariant, weight: f64, crossover_rate: f64) -> Self {
+    assert!((0.0..=2.0).contains(&weight));
+    assert!((0.0..=1.0).contains(&crossover_rate));
+
+    Self {
+      variant,
+      weight,
+      crossover_rate,
+      targets: Vec::new(),
+    }
+  }
+
+  pub fn targets(&self) -> impl Iterator<Item = (&Chromosome, f64)> {
+    self.targets
+      .iter()
+      .map(|target| (&target.chromosome, target.fitness))
+  }
+
+  fn select<I>(&mut self, population: &[I])
+    where I: Individual<Gene = f64> {
+    if self.targets.len() != population.len() {
+      self.targets = population.iter().map(Evaluated::new).collect();
+      return;
+    }
+
+    for (target, trial) in self.targets.iter_mut().zip(population) {
+      if trial.fitness() >= target.fitness {
+        *target = Evaluated::new(trial);
+      }
+    }
+  }
+
+  fn trial(&self, rng: &mut impl RngCore, index: usize, best: usize) -> Chromosome {
+    let len = self.targets.len();
+    let mut pick = |excluded: &[usize]| loop {
+      let candidate = gen_index(rng, len);
+      if !excluded.contains(&candidate) {
+        return candidate;
+      }
+    };
+
+    let base = match self.variant {
+      DifferentialVariant::Rand1Bin => pick(&[index]),
+      DifferentialVariant::Best1Bin => best,
+    };
+    let a = pick(&[index, base]);
+    let b = pick(&[index, base, a]);
+
+    let target = &self.targets[index].chromosome;
+    let base = &self.targets[base].chromosome;
+    let a = &self.targets[a].chromosome;
+    let b = &self.targets[b].chromosome;
+
+    // binomial crossover, one gene is always taken from the mutant
+    let forced = gen_index(rng, target.len());
+
+    (0..target.len())
+      .map(|gene| {
+        if gene == forced || rng.gen_bool(self.crossover_rate) {
+          base[gene] + self.weight * (a[gene] - b[gene])
+        } else {
+          target[gene]
+        }
+      })
+      .collect()
+  }
+}
+
+impl Optimizer for DifferentialEvolution {
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = f64> {
+    assert!(population.len() >= 4, "Error - differential evolution needs at least 4 individuals");
+
+    self.select(population);
+
+    let best = (0..self.targets.len())
+      .max_by(|&a, &b| self.targets[a].fitness.total_cmp(&self.targets[b].fitness))
+      .unwrap_or_default();
+
+    let trials = (0..self.targets.len())
+      .map(|index| I::from(self.trial(rng, index, best)))
+      .collect();
+
+    let stats = Statistics::new(population);
+
+    (trials, stats)
+  }
+
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    save(&self.targets)
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    self.targets = load(state)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::TestIndividual;
+
+  fn population(fitnesses: &[f64]) -> Vec<TestIndividual> {
+    fitnesses
+      .iter()
+      .enumerate()
+      .map(|(i, &fitness)| TestIndividual::new(&[i as f64, 0.0, 0.0], fitness))
+      .collect()
+  }
+
+  #[test]
+  fn trials_replace_only_worse_targets() {
+    let mut rng = crate::testing::rng();
+    let mut de = DifferentialEvolution::new(DifferentialVariant::Rand1Bin, 0.5, 0.9);
+
+    de.next_generation(&mut rng, &population(&[1.0, 2.0, 3.0, 4.0]));
+    de.next_generation(&mut rng, &population(&[0.0, 2.0, 5.0, 1.0]));
+
+    let fitnesses: Vec<_> = de.targets().map(|(_, fitness)| fitness).collect();
+    assert_eq!(fitnesses, [1.0, 2.0, 5.0, 4.0]);
+  }
+
+  #[test]
+  fn no_crossover_still_takes_one_mutant_gene() {
+    let mut rng = crate::testing::rng();
+    let mut de = DifferentialEvolution::new(DifferentialVariant::Best1Bin, 1.0, 0.0);
+    let population: Vec<_> = (0..6)
+      .map(|i| TestIndividual::new(&[i as f64, 10.0 * i as f64, 100.0 * i as f64], i as f64))
+      .collect();
+
+    let (trials, _) = de.next_generation(&mut rng, &population);
+
+    for (trial, target) in trials.iter().zip(&population) {
+      let changed = trial.chromosome
+        .iter()
+        .zip(target.chromosome.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+      assert!(changed <= 1);
+    }
+  }
+}
@@ -0,0 +1,465 @@
+use std::ptr;
+use std::sync::OnceLock;
+
+use crate::*;
+
+// object-safe counterparts of the operator traits, implemented for every
+// operator, so operators can be picked at runtime and boxed; stateful
+// wrappers keep working, but lose their accessors (`species`, `archive`...);
+// boxed operators are `Send + Sync`, so whatever holds them can be too
+pub trait DynSelectionMethod: Send + Sync {
+  fn dyn_select(&self, rng: &mut dyn RngCore, population: &[Candidate]) -> usize;
+  fn dyn_select_parents(&self, rng: &mut dyn RngCore, population: &[Candidate]) -> [usize; 2];
+  fn dyn_select_parents_unprepared(&self, rng: &mut dyn RngCore, population: &[Candidate]) -> [usize; 2];
+  fn dyn_prepare(&mut self, population: &[Candidate]);
+  #[cfg(feature = "serde")]
+  fn dyn_save_state(&self) -> serde_json::Value;
+  #[cfg(feature = "serde")]
+  fn dyn_load_state(&mut self, state: serde_json::Value) -> std::io::Result<()>;
+}
+
+pub trait DynCrossoverMethod<G = f64>: Send + Sync {
+  fn dyn_crossover(&self, rng: &mut dyn RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G>;
+}
+
+pub trait DynMutationMethod<G = f64>: Send + Sync {
+  fn dyn_mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>);
+  fn dyn_adapt(&mut self, history: &[Statistics]);
+}
+
+// type-erased individual that boxed selection methods choose among; its genes
+// are seen as real numbers (`Gene::value`), converted only once a selection
+// method looks at them
+#[derive(Clone)]
+pub struct Candidate<'a> {
+  individual: Option<&'a dyn ErasedIndividual>, // none for offspring, which are never selected
+  index: usize, // within the population it is selected from
+  chromosome: OnceLock<Chromosome>,
+}
+
+impl<'a> Candidate<'a> {
+  pub fn new<I: Individual>(index: usize, individual: &'a I) -> Self {
+    Self {
+      individual: Some(individual),
+      index,
+      chromosome: OnceLock::new(),
+    }
+  }
+}
+
+impl Individual for Candidate<'_> {
+  type Gene = f64;
+
+  fn fitness(&self) -> f64 {
+    self.individual.map_or(0.0, |individual| individual.fitness())
+  }
+
+  fn behaviour(&self) -> Option<Vec<f64>> {
+    self.individual.and_then(|individual| individual.behaviour())
+  }
+
+  fn violation(&self) -> f64 {
+    self.individual.map_or(0.0, |individual| individual.violation())
+  }
+
+  fn objectives(&self) -> Option<Vec<f64>> {
+    self.individual.and_then(|individual| individual.objectives())
+  }
+}
+
+impl AsRef<Chromosome> for Candidate<'_> {
+  fn as_ref(&self) -> &Chromosome {
+    self.chromosome.get_or_init(|| {
+      self.individual
+        .expect("Error - offspring candidates are built with their chromosome")
+        .values()
+    })
+  }
+}
+
+impl From<Chromosome> for Candidate<'_> {
+  fn from(chromosome: Chromosome) -> Self {
+    Self {
+      individual: None,
+      index: usize::MAX,
+      chromosome: OnceLock::from(chromosome),
+    }
+  }
+}
+
+// what a candidate needs of the individual behind it, object-safe
+trait ErasedIndividual: MaybeSync {
+  fn fitness(&self) -> f64;
+  fn behaviour(&self) -> Option<Vec<f64>>;
+  fn violation(&self) -> f64;
+  fn objectives(&self) -> Option<Vec<f64>>;
+  fn values(&self) -> Chromosome;
+}
+
+impl<I> ErasedIndividual for I
+  where I: Individual, {
+
+  fn fitness(&self) -> f64 {
+    Individual::fitness(self)
+  }
+
+  fn behaviour(&self) -> Option<Vec<f64>> {
+    Individual::behaviour(self)
+  }
+
+  fn violation(&self) -> f64 {
+    Individual::violation(self)
+  }
+
+  fn objectives(&self) -> Option<Vec<f64>> {
+    Individual::objectives(self)
+  }
+
+  fn values(&self) -> Chromosome {
+    self.as_ref().values()
+  }
+}
+
+impl<S> DynSelectionMethod for S
+  where S: SelectionMethod + Send + Sync, {
+
+  fn dyn_select(&self, mut rng: &mut dyn RngCore, population: &[Candidate]) -> usize {
+    position(population, self.select(&mut rng, population))
+  }
+
+  fn dyn_select_parents(&self, mut rng: &mut dyn RngCore, population: &[Candidate]) -> [usize; 2] {
+    self.select_parents(&mut rng, population)
+      .map(|selected| position(population, selected))
+  }
+
+  fn dyn_select_parents_unprepared(&self, mut rng: &mut dyn RngCore, population: &[Candidate]) -> [usize; 2] {
+    self.select_parents_unprepared(&mut rng, population)
+      .map(|selected| position(population, selected))
+  }
+
+  fn dyn_prepare(&mut self, population: &[Candidate]) {
+    self.prepare(population)
+  }
+
+  #[cfg(feature = "serde")]
+  fn dyn_save_state(&self) -> serde_json::Value {
+    self.save_state()
+  }
+
+  #[cfg(feature = "serde")]
+  fn dyn_load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    self.load_state(state)
+  }
+}
+
+// candidates know their index, checked rather than searched for
+fn position(population: &[Candidate], selected: &Candidate) -> usize {
+  let index = selected.index;
+  let candidate = population
+    .get(index)
+    .expect("Error - selected individual is not in the population");
+  assert!(ptr::eq(candidate, selected), "Error - selected individual is not in the population");
+
+  index
+}
+
+impl<C, G> DynCrossoverMethod<G> for C
+  where C: CrossoverMethod<G> + Send + Sync, {
+
+  fn dyn_crossover(&self, mut rng: &mut dyn RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G> {
+    self.crossover(&mut rng, parent_a, parent_b)
+  }
+}
+
+impl<M, G> DynMutationMethod<G> for M
+  where M: MutationMethod<G> + Send + Sync, {
+
+  fn dyn_mutate(&self, mut rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+    self.mutate(&mut rng, child)
+  }
+
+  fn dyn_adapt(&mut self, history: &[Statistics]) {
+    self.adapt(history)
+  }
+}
+
+// deref first in here, the box itself also picks up the blanket impl
+impl SelectionMethod for Box<dyn DynSelectionMethod> {
+  fn select<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+    where I: Individual {
+    &population[(**self).dyn_select(rng, &candidates(population))]
+  }
+
+  fn select_parents<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    (**self)
+      .dyn_select_parents(rng, &candidates(population))
+      .map(|index| &population[index])
+  }
+
+  // every child's parents are selected from the same candidates
+  fn with_parents<'a, I, T>(&self, population: &'a [I], breed: impl FnOnce(&dyn Fn(&mut dyn RngCore) -> [&'a I; 2]) -> T) -> T
+    where I: Individual {
+    let candidates = candidates(population);
+
+    breed(&|rng| {
+      (**self)
+        .dyn_select_parents(rng, &candidates)
+        .map(|index| &population[index])
+    })
+  }
+
+  fn select_parents_unprepared<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    (**self)
+      .dyn_select_parents_unprepared(rng, &candidates(population))
+      .map(|index| &population[index])
+  }
+
+  fn prepare<I>(&mut self, population: &[I])
+    where I: Individual {
+    (**self).dyn_prepare(&candidates(population))
+  }
+
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    (**self).dyn_save_state()
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    (**self).dyn_load_state(state)
+  }
+}
+
+fn candidates<I: Individual>(population: &[I]) -> Vec<Candidate<'_>> {
+  population
+    .iter()
+    .enumerate()
+    .map(|(index, individual)| Candidate::new(index, individual))
+    .collect()
+}
+
+impl<G> CrossoverMethod<G> for Box<dyn DynCrossoverMethod<G>> {
+  fn crossover(&self, rng: &mut impl RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G> {
+    (**self).dyn_crossover(rng, parent_a, parent_b)
+  }
+}
+
+impl<G> MutationMethod<G> for Box<dyn DynMutationMethod<G>> {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome<G>) {
+    (**self).dyn_mutate(rng, child)
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    (**self).dyn_adapt(history)
+  }
+}
+
+pub type DynGeneticAlgorithm = GeneticAlgorithm<
+  Box<dyn DynSelectionMethod>,
+  Box<dyn DynCrossoverMethod>,
+  Box<dyn DynMutationMethod>,
+>;
+
+// any of the optimizers, picked at runtime; `Optimizer` has generic methods,
+// so unlike the operators it cannot be boxed
+pub enum DynOptimizer {
+  GeneticAlgorithm(DynGeneticAlgorithm),
+  CmaEs(CmaEs),
+  DifferentialEvolution(DifferentialEvolution),
+  EvolutionStrategy(EvolutionStrategy<Box<dyn DynMutationMethod>>),
+  Nsga2(Nsga2<Box<dyn DynCrossoverMethod>, Box<dyn DynMutationMethod>>),
+}
+
+impl Optimizer for DynOptimizer {
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = f64> {
+    match self {
+      Self::GeneticAlgorithm(optimizer) => optimizer.next_generation(rng, population),
+      Self::CmaEs(optimizer) => optimizer.next_generation(rng, population),
+      Self::DifferentialEvolution(optimizer) => optimizer.next_generation(rng, population),
+      Self::EvolutionStrategy(optimizer) => optimizer.next_generation(rng, population),
+      Self::Nsga2(optimizer) => optimizer.next_generation(rng, population),
+    }
+  }
+
+  fn next_generation_with<I>(
+    &mut self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn Observer,
+    hook: &mut dyn BreedHook<I>,
+  ) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = f64> {
+    match self {
+      Self::GeneticAlgorithm(optimizer) => optimizer.next_generation_with(rng, population, observer, hook),
+      Self::CmaEs(optimizer) => optimizer.next_generation_with(rng, population, observer, hook),
+      Self::DifferentialEvolution(optimizer) => optimizer.next_generation_with(rng, population, observer, hook),
+      Self::EvolutionStrategy(optimizer) => optimizer.next_generation_with(rng, population, observer, hook),
+      Self::Nsga2(optimizer) => optimizer.next_generation_with(rng, population, observer, hook),
+    }
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    match self {
+      Self::GeneticAlgorithm(optimizer) => optimizer.adapt(history),
+      Self::CmaEs(optimizer) => optimizer.adapt(history),
+      Self::DifferentialEvolution(optimizer) => optimizer.adapt(history),
+      Self::EvolutionStrategy(optimizer) => optimizer.adapt(history),
+      Self::Nsga2(optimizer) => optimizer.adapt(history),
+    }
+  }
+
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    match self {
+      Self::GeneticAlgorithm(optimizer) => optimizer.save_state(),
+      Self::CmaEs(optimizer) => optimizer.save_state(),
+      Self::DifferentialEvolution(optimizer) => optimizer.save_state(),
+      Self::EvolutionStrategy(optimizer) => optimizer.save_state(),
+      Self::Nsga2(optimizer) => optimizer.save_state(),
+    }
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    match self {
+      Self::GeneticAlgorithm(optimizer) => optimizer.load_state(state),
+      Self::CmaEs(optimizer) => optimizer.load_state(state),
+      Self::DifferentialEvolution(optimizer) => optimizer.load_state(state),
+      Self::EvolutionStrategy(optimizer) => optimizer.load_state(state),
+      Self::Nsga2(optimizer) => optimizer.load_state(state),
+    }
+  }
+}
+
+impl Default for DynOptimizer {
+  fn default() -> Self {
+    OptimizerConfig::default()
+      .build()
+      .expect("Error - default config is valid")
+  }
+}
+
+impl From<DynGeneticAlgorithm> for DynOptimizer {
+  fn from(optimizer: DynGeneticAlgorithm) -> Self {
+    Self::GeneticAlgorithm(optimizer)
+  }
+}
+
+impl From<CmaEs> for DynOptimizer {
+  fn from(optimizer: CmaEs) -> Self {
+    Self::CmaEs(optimizer)
+  }
+}
+
+impl From<DifferentialEvolution> for DynOptimizer {
+  fn from(optimizer: DifferentialEvolution) -> Self {
+    Self::DifferentialEvolution(optimizer)
+  }
+}
+
+impl From<EvolutionStrategy<Box<dyn DynMutationMethod>>> for DynOptimizer {
+  fn from(optimizer: EvolutionStrategy<Box<dyn DynMutationMethod>>) -> Self {
+    Self::EvolutionStrategy(optimizer)
+  }
+}
+
+impl From<Nsga2<Box<dyn DynCrossoverMethod>, Box<dyn DynMutationMethod>>> for DynOptimizer {
+  fn from(optimizer: Nsga2<Box<dyn DynCrossoverMethod>, Box<dyn DynMutationMethod>>) -> Self {
+    Self::Nsga2(optimizer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  // picks whoever has the largest first gene, which only works on real genes
+  struct FirstGene;
+
+  impl SelectionMethod for FirstGene {
+    fn select<'a, I>(&self, _rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+      where I: Individual {
+      population
+        .iter()
+        .max_by(|a, b| a.as_ref()[0].value().total_cmp(&b.as_ref()[0].value()))
+        .unwrap()
+    }
+  }
+
+  #[test]
+  fn boxed_selection_sees_genes() {
+    let population = [
+      TestIndividual::new(&[0.0], 1.0),
+      TestIndividual::new(&[2.0], 1.0),
+      TestIndividual::new(&[1.0], 1.0),
+    ];
+    let selection: Box<dyn DynSelectionMethod> = Box::new(FirstGene);
+
+    let selected = selection.select(&mut testing::rng(), &population);
+
+    assert!(ptr::eq(selected, &population[1]));
+  }
+
+  // remembers where every population it selected from lay
+  struct Recording(std::sync::Arc<std::sync::Mutex<Vec<usize>>>);
+
+  impl SelectionMethod for Recording {
+    fn select<'a, I>(&self, _rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+      where I: Individual {
+      self.0.lock().unwrap().push(population.as_ptr() as usize);
+      &population[population.len() - 1]
+    }
+  }
+
+  #[test]
+  fn boxed_selection_converts_a_generation_once() {
+    let seen = std::sync::Arc::default();
+    let selection: Box<dyn DynSelectionMethod> = Box::new(Recording(std::sync::Arc::clone(&seen)));
+    let ga = GeneticAlgorithm::new(selection, UniformCrossover::new(), GaussianMutation::new(0.0, 0.0));
+    let population: Vec<_> = (0..5)
+      .map(|i| TestIndividual::new(&[i as f64], 1.0))
+      .collect();
+
+    let (children, _) = ga.evolve_observed(&mut testing::rng(), &population, &mut ());
+
+    // two selections per child, all from the same candidates
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 10);
+    assert!(seen.iter().all(|&address| address == seen[0]));
+    assert!(children.iter().all(|child: &TestIndividual| child.chromosome[0] == 4.0));
+  }
+
+  #[test]
+  fn candidates_see_genes_as_values() {
+    let individual = Bits([true, false].into_iter().collect());
+    let candidate = Candidate::new(0, &individual);
+
+    assert!(candidate.as_ref().iter().eq([1.0, 0.0].iter()));
+  }
+
+  struct Bits(Chromosome<bool>);
+
+  impl Individual for Bits {
+    type Gene = bool;
+
+    fn fitness(&self) -> f64 {
+      1.0
+    }
+  }
+
+  impl AsRef<Chromosome<bool>> for Bits {
+    fn as_ref(&self) -> &Chromosome<bool> {
+      &self.0
+    }
+  }
+
+  impl From<Chromosome<bool>> for Bits {
+    fn from(chromosome: Chromosome<bool>) -> Self {
+      Self(chromosome)
+    }
+  }
+}
@@ -0,0 +1,298 @@
+use rand::Rng;
+use rand::RngCore;
+
+use std::fmt::Debug;
+
+use crate::*;
+
+// anything a chromosome can be made of
+pub trait Gene: Clone + Debug + PartialEq + MaybeSync {
+  // how far apart two genes are, chromosome distance is the euclidean norm of these
+  fn difference(&self, other: &Self) -> f64;
+
+  // the gene as a real number, which is how type-erased views see it
+  fn value(&self) -> f64;
+}
+
+impl Gene for f64 {
+  fn difference(&self, other: &Self) -> f64 {
+    self - other
+  }
+
+  fn value(&self) -> f64 {
+    *self
+  }
+}
+
+impl Gene for bool {
+  fn difference(&self, other: &Self) -> f64 {
+    if self == other { 0.0 } else { 1.0 }
+  }
+
+  fn value(&self) -> f64 {
+    if *self { 1.0 } else { 0.0 }
+  }
+}
+
+impl Gene for i64 {
+  fn difference(&self, other: &Self) -> f64 {
+    (self - other) as f64
+  }
+
+  fn value(&self) -> f64 {
+    *self as f64
+  }
+}
+
+// permutation genes, compared like integers
+impl Gene for usize {
+  fn difference(&self, other: &Self) -> f64 {
+    *self as f64 - *other as f64
+  }
+
+  fn value(&self) -> f64 {
+    *self as f64
+  }
+}
+
+// genes of different kinds in one chromosome; integers carry their own bounds
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MixedGene {
+  Real(f64),
+  Integer { value: i64, min: i64, max: i64 },
+  Bit(bool),
+}
+
+impl Gene for MixedGene {
+  fn difference(&self, other: &Self) -> f64 {
+    match (self, other) {
+      (Self::Real(a), Self::Real(b)) => a.difference(b),
+      (Self::Integer { value: a, .. }, Self::Integer { value: b, .. }) => a.difference(b),
+      (Self::Bit(a), Self::Bit(b)) => a.difference(b),
+      _ => 1.0,
+    }
+  }
+
+  fn value(&self) -> f64 {
+    match self {
+      Self::Real(value) => *value,
+      Self::Integer { value, .. } => value.value(),
+      Self::Bit(bit) => bit.value(),
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitFlipMutation {
+  chance: f64, // chance of flipping
+}
+
+impl BitFlipMutation {
+  pub fn new(chance: f64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    Self { chance }
+  }
+}
+
+impl MutationMethod<bool> for BitFlipMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome<bool>) {
+    for gene in child.iter_mut() {
+      if rng.gen_bool(self.chance) {
+        *gene = !*gene;
+      }
+    }
+  }
+}
+
+// creep mutation, integers move by up to `step` and stay within bounds
+#[derive(Clone, Debug)]
+pub struct IntegerMutation {
+  chance: f64,
+  step: i64,
+  min: i64,
+  max: i64,
+}
+
+impl IntegerMutation {
+  pub fn new(chance: f64, step: i64, min: i64, max: i64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    assert!(step > 0);
+    assert!(min <= max);
+    Self { chance, step, min, max }
+  }
+}
+
+impl MutationMethod<i64> for IntegerMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome<i64>) {
+    for gene in child.iter_mut() {
+      if rng.gen_bool(self.chance) {
+        *gene = (*gene + rng.gen_range(-self.step..=self.step)).clamp(self.min, self.max);
+      }
+    }
+  }
+}
+
+// exchanges two genes, so permutations stay permutations
+#[derive(Clone, Debug)]
+pub struct SwapMutation {
+  chance: f64, // chance of a swap per gene
+}
+
+impl SwapMutation {
+  pub fn new(chance: f64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    Self { chance }
+  }
+}
+
+impl<G> MutationMethod<G> for SwapMutation
+  where G: Gene, {
+
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome<G>) {
+    let len = child.len();
+
+    for i in 0..len {
+      if rng.gen_bool(self.chance) {
+        child.genes.swap(i, gen_index(rng, len));
+      }
+    }
+  }
+}
+
+// davis' order crossover: a random slice of the first parent is kept in place
+// and the remaining genes follow in the order they appear in the second parent
+#[derive(Clone, Debug, Default)]
+pub struct OrderCrossover;
+
+impl OrderCrossover {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+impl<G> CrossoverMethod<G> for OrderCrossover
+  where G: Gene, {
+
+  fn crossover(&self, rng: &mut impl RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G> {
+    assert_eq!(parent_a.len(), parent_b.len());
+
+    let len = parent_a.len();
+    if len == 0 {
+      return parent_a.clone();
+    }
+
+    let mut start = gen_index(rng, len);
+    let mut end = gen_index(rng, len);
+    if start > end {
+      (start, end) = (end, start);
+    }
+
+    let kept = &parent_a.genes[start..=end];
+    let mut rest = parent_b.genes
+      .iter()
+      .filter(|gene| !kept.contains(gene));
+
+    let genes = (0..len)
+      .map(|i| if (start..=end).contains(&i) {
+        parent_a.genes[i].clone()
+      } else {
+        rest.next().expect("Error - parents are not permutations of each other").clone()
+      })
+      .collect();
+
+    Chromosome { genes, step_sizes: Vec::new() }
+  }
+}
+
+// mutates every kind of gene its own way: reals by a uniform amount up to
+// `mag`, integers by up to `step` within their bounds, and bits by flipping
+#[derive(Clone, Debug)]
+pub struct MixedMutation {
+  chance: f64,
+  mag: f64,
+  step: i64,
+}
+
+impl MixedMutation {
+  pub fn new(chance: f64, mag: f64, step: i64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    assert!(step > 0);
+    Self { chance, mag, step }
+  }
+}
+
+impl MutationMethod<MixedGene> for MixedMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome<MixedGene>) {
+    for gene in child.iter_mut() {
+      if !rng.gen_bool(self.chance) {
+        continue;
+      }
+
+      match gene {
+        MixedGene::Real(value) => *value += rng.gen_range(-self.mag..=self.mag),
+        MixedGene::Integer { value, min, max } => {
+          *value = (*value + rng.gen_range(-self.step..=self.step)).clamp(*min, *max);
+        }
+        MixedGene::Bit(bit) => *bit = !*bit,
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn is_permutation(chromosome: &Chromosome<usize>, len: usize) -> bool {
+    let mut genes: Vec<_> = chromosome.iter().copied().collect();
+    genes.sort_unstable();
+    genes == (0..len).collect::<Vec<_>>()
+  }
+
+  #[test]
+  fn order_crossover_keeps_permutations() {
+    let mut rng = crate::testing::rng();
+    let parent_a: Chromosome<usize> = (0..8).collect();
+    let parent_b: Chromosome<usize> = (0..8).rev().collect();
+
+    for _ in 0..100 {
+      let child = OrderCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+      assert!(is_permutation(&child, 8));
+    }
+  }
+
+  #[test]
+  fn swap_mutation_keeps_permutations() {
+    let mut rng = crate::testing::rng();
+    let mut child: Chromosome<usize> = (0..8).collect();
+
+    for _ in 0..100 {
+      SwapMutation::new(0.5).mutate(&mut rng, &mut child);
+      assert!(is_permutation(&child, 8));
+    }
+  }
+
+  #[test]
+  fn mixed_mutation_keeps_integers_in_bounds() {
+    let mut rng = crate::testing::rng();
+    let mut child: Chromosome<MixedGene> = [
+      MixedGene::Integer { value: 0, min: 0, max: 3 },
+      MixedGene::Integer { value: 3, min: 0, max: 3 },
+    ].into_iter().collect();
+
+    for _ in 0..100 {
+      MixedMutation::new(1.0, 0.0, 2).mutate(&mut rng, &mut child);
+      for gene in child.iter() {
+        assert!(matches!(gene, MixedGene::Integer { value: 0..=3, .. }));
+      }
+    }
+  }
+
+  #[test]
+  fn values() {
+    assert_eq!(MixedGene::Real(0.5).value(), 0.5);
+    assert_eq!(MixedGene::Integer { value: 3, min: 0, max: 5 }.value(), 3.0);
+    assert_eq!(MixedGene::Bit(true).value(), 1.0);
+  }
+}
@@ -0,0 +1,63 @@
+use crate::*;
+
+// sees every child an optimizer produces as it is born, to attach whatever the
+// chromosome does not carry (ids, traits...) from the parents it was bred from
+pub trait BreedHook<I: Individual> {
+  // `child` was bred from `parents`, `crossed` is its chromosome before mutation
+  fn bred(&mut self, _rng: &mut dyn RngCore, _parents: [&I; 2], _crossed: &Chromosome<I::Gene>, _child: &mut I) {}
+
+  // `child` has no parents the optimizer can name, like one sampled from a
+  // distribution or mutated from a parent the optimizer keeps to itself
+  fn founded(&mut self, _rng: &mut dyn RngCore, _child: &mut I) {}
+}
+
+// hooks nothing
+impl<I> BreedHook<I> for ()
+  where I: Individual, {}
+
+impl<I, H> BreedHook<I> for &mut H
+  where I: Individual,
+        H: BreedHook<I> + ?Sized, {
+
+  fn bred(&mut self, rng: &mut dyn RngCore, parents: [&I; 2], crossed: &Chromosome<I::Gene>, child: &mut I) {
+    (**self).bred(rng, parents, crossed, child)
+  }
+
+  fn founded(&mut self, rng: &mut dyn RngCore, child: &mut I) {
+    (**self).founded(rng, child)
+  }
+}
+
+impl<I, H> BreedHook<I> for Option<H>
+  where I: Individual,
+        H: BreedHook<I>, {
+
+  fn bred(&mut self, rng: &mut dyn RngCore, parents: [&I; 2], crossed: &Chromosome<I::Gene>, child: &mut I) {
+    if let Some(hook) = self {
+      hook.bred(rng, parents, crossed, child);
+    }
+  }
+
+  fn founded(&mut self, rng: &mut dyn RngCore, child: &mut I) {
+    if let Some(hook) = self {
+      hook.founded(rng, child);
+    }
+  }
+}
+
+// both hooks, in order
+impl<I, A, B> BreedHook<I> for (A, B)
+  where I: Individual,
+        A: BreedHook<I>,
+        B: BreedHook<I>, {
+
+  fn bred(&mut self, rng: &mut dyn RngCore, parents: [&I; 2], crossed: &Chromosome<I::Gene>, child: &mut I) {
+    self.0.bred(rng, parents, crossed, child);
+    self.1.bred(rng, parents, crossed, child);
+  }
+
+  fn founded(&mut self, rng: &mut dyn RngCore, child: &mut I) {
+    self.0.founded(rng, child);
+    self.1.founded(rng, child);
+  }
+}
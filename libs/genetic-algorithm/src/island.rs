@@ -0,0 +1,191 @@
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationTopology {
+  Ring, // every island sends to the next one
+  FullyConnected, // every island sends to every other one
+}
+
+impl MigrationTopology {
+  // islands receiving migrants from `island`
+  fn neighbours(self, island: usize, islands: usize) -> Vec<usize> {
+    match self {
+      Self::Ring if islands > 1 => vec![(island + 1) % islands],
+      Self::Ring => Vec::new(),
+      Self::FullyConnected => (0..islands).filter(|&i| i != island).collect(),
+    }
+  }
+}
+
+// sub-populations evolved independently by their own genetic algorithm, with
+// the best individuals of every island periodically copied over the worst
+// individuals of its neighbours
+pub struct IslandModel<S, C, M> {
+  islands: Vec<GeneticAlgorithm<S, C, M>>,
+  topology: MigrationTopology,
+  interval: usize, // generations between migrations
+  migrants: usize, // individuals sent to every neighbour
+}
+
+impl<S, C, M> IslandModel<S, C, M>
+  where S: SelectionMethod, {
+
+  pub fn new(islands: Vec<GeneticAlgorithm<S, C, M>>, topology: MigrationTopology, interval: usize, migrants: usize) -> Self {
+    assert!(!islands.is_empty());
+    assert!(interval > 0);
+
+    Self {
+      islands,
+      topology,
+      interval,
+      migrants,
+    }
+  }
+
+  pub fn islands(&self) -> &[GeneticAlgorithm<S, C, M>] {
+    &self.islands
+  }
+
+  pub fn islands_mut(&mut self) -> &mut [GeneticAlgorithm<S, C, M>] {
+    &mut self.islands
+  }
+
+  // migrate when due, then evolve every island's population in place
+  pub fn evolve<I>(&mut self, rng: &mut impl RngCore, generation: usize, populations: &mut [Vec<I>]) -> Vec<Statistics>
+    where I: Individual + Clone,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    assert_eq!(populations.len(), self.islands.len());
+
+    if generation > 0 && generation.is_multiple_of(self.interval) {
+      self.migrate(populations);
+    }
+
+    self.islands
+      .iter_mut()
+      .zip(populations.iter_mut())
+      .map(|(island, population)| {
+        island.prepare(population);
+        let (evolved_population, stats) = island.evolve(rng, population);
+        let evolved_population: Vec<_> = evolved_population.collect();
+
+        *population = evolved_population;
+        stats
+      })
+      .collect()
+  }
+
+  pub fn migrate<I>(&self, populations: &mut [Vec<I>])
+    where I: Individual + Clone {
+    let islands = populations.len();
+
+    // pick every emigrant before anyone arrives, so nobody migrates twice
+    let emigrants: Vec<Vec<I>> = populations
+      .iter()
+      .map(|population| {
+        by_fitness(population)
+          .into_iter()
+          .rev()
+          .take(self.migrants)
+          .map(|index| population[index].clone())
+          .collect()
+      })
+      .collect();
+
+    let mut immigrants: Vec<Vec<I>> = vec![Vec::new(); islands];
+    for (island, emigrants) in emigrants.iter().enumerate() {
+      for neighbour in self.topology.neighbours(island, islands) {
+        immigrants[neighbour].extend(emigrants.iter().cloned());
+      }
+    }
+
+    for (population, immigrants) in populations.iter_mut().zip(immigrants) {
+      let worst = by_fitness(population);
+
+      for (index, immigrant) in worst.into_iter().zip(immigrants) {
+        population[index] = immigrant;
+      }
+    }
+  }
+}
+
+// indices of the population from lowest to highest fitness
+fn by_fitness<I>(population: &[I]) -> Vec<usize>
+  where I: Individual {
+  let mut indices: Vec<_> = (0..population.len()).collect();
+  indices.sort_by(|&a, &b| population[a].fitness().total_cmp(&population[b].fitness()));
+  indices
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{rng, TestIndividual};
+
+  fn model(islands: usize, topology: MigrationTopology, migrants: usize) -> IslandModel<RouletteWheelSelection, UniformCrossover, GaussianMutation> {
+    let islands = (0..islands)
+      .map(|_| GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover::new(), GaussianMutation::new(0.5, 0.5)))
+      .collect();
+    IslandModel::new(islands, topology, 2, migrants)
+  }
+
+  // island `island` holds fitnesses island*10 + 1..=island*10 + 4
+  fn populations(islands: usize) -> Vec<Vec<TestIndividual>> {
+    (0..islands)
+      .map(|island| {
+        (1..=4)
+          .map(|i| {
+            let fitness = (island * 10 + i) as f64;
+            TestIndividual::new(&[fitness], fitness)
+          })
+          .collect()
+      })
+      .collect()
+  }
+
+  fn fitnesses(population: &[TestIndividual]) -> Vec<f64> {
+    let mut fitnesses: Vec<_> = population.iter().map(|individual| individual.fitness).collect();
+    fitnesses.sort_by(f64::total_cmp);
+    fitnesses
+  }
+
+  #[test]
+  fn ring_sends_the_best_over_the_next_islands_worst() {
+    let mut populations = populations(3);
+    model(3, MigrationTopology::Ring, 2).migrate(&mut populations);
+
+    assert_eq!(fitnesses(&populations[0]), [3.0, 4.0, 23.0, 24.0]);
+    assert_eq!(fitnesses(&populations[1]), [3.0, 4.0, 13.0, 14.0]);
+    assert_eq!(fitnesses(&populations[2]), [13.0, 14.0, 23.0, 24.0]);
+  }
+
+  #[test]
+  fn fully_connected_sends_to_every_other_island() {
+    let mut populations = populations(3);
+    model(3, MigrationTopology::FullyConnected, 1).migrate(&mut populations);
+
+    assert_eq!(fitnesses(&populations[0]), [3.0, 4.0, 14.0, 24.0]);
+    assert_eq!(fitnesses(&populations[1]), [4.0, 13.0, 14.0, 24.0]);
+    assert_eq!(fitnesses(&populations[2]), [4.0, 14.0, 23.0, 24.0]);
+  }
+
+  #[test]
+  fn a_lone_ring_island_keeps_its_population() {
+    let mut populations = populations(1);
+    model(1, MigrationTopology::Ring, 2).migrate(&mut populations);
+
+    assert_eq!(fitnesses(&populations[0]), [1.0, 2.0, 3.0, 4.0]);
+  }
+
+  #[test]
+  fn migrates_only_every_interval() {
+    let mut model = model(2, MigrationTopology::Ring, 1);
+
+    // statistics describe the population once any migrants arrived
+    let stats = model.evolve(&mut rng(), 1, &mut populations(2));
+    assert_eq!(stats[1].min_fitness(), 11.0);
+
+    let stats = model.evolve(&mut rng(), 2, &mut populations(2));
+    assert_eq!(stats[1].min_fitness(), 4.0);
+  }
+}
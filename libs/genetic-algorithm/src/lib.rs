@@ -6,13 +6,224 @@ use rand::seq::SliceRandom;
 
 use std::ops::Index;
 
-pub trait Individual: AsRef<Chromosome> + From<Chromosome> {
+mod bounds;
+mod builder;
+mod cmaes;
+mod coevolution;
+mod differential;
+mod dynamic;
+mod gene;
+mod hook;
+mod island;
+mod lineage;
+mod mutation;
+mod novelty;
+mod nsga2;
+mod observer;
+mod optimizer;
+mod run;
+mod scaling;
+mod schedule;
+mod species;
+mod steady;
+mod strategy;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "serde")]
+mod checkpoint;
+#[cfg(test)]
+mod testing;
+
+pub use self::{
+  bounds::*,
+  builder::*,
+  cmaes::*,
+  coevolution::*,
+  differential::*,
+  dynamic::*,
+  gene::*,
+  hook::*,
+  island::*,
+  lineage::*,
+  mutation::*,
+  novelty::*,
+  nsga2::*,
+  observer::*,
+  optimizer::*,
+  run::*,
+  scaling::*,
+  schedule::*,
+  species::*,
+  steady::*,
+  strategy::*,
+};
+
+#[cfg(feature = "serde")]
+pub use self::checkpoint::*;
+
+// `Send + Sync` with the parallel feature, so the same bounds breed across
+// threads there, and nothing without it
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T> MaybeSync for T
+  where T: Send + Sync + ?Sized, {}
+
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T
+  where T: ?Sized, {}
+
+pub trait Individual: AsRef<Chromosome<Self::Gene>> + From<Chromosome<Self::Gene>> + MaybeSync {
+  type Gene: Gene;
+
   fn fitness(&self) -> f64;
+
+  // descriptor of what the individual did, only needed for novelty search
+  fn behaviour(&self) -> Option<Vec<f64>> {
+    None
+  }
+
+  // how far the individual is from satisfying its constraints, 0 when it
+  // does; only weighed in when the algorithm has a `Penalty`
+  fn violation(&self) -> f64 {
+    0.0
+  }
+
+  // scores on several objectives at once, all of them maximized; only needed
+  // for nsga-ii, which treats `fitness` as the one objective otherwise
+  fn objectives(&self) -> Option<Vec<f64>> {
+    None
+  }
+}
+
+// view of a borrowed individual under a different fitness, used by selection
+// wrappers that score individuals by something other than `fitness`
+enum Scored<'a, I: Individual> {
+  Borrowed(&'a I, f64),
+  Offspring(Chromosome<I::Gene>), // only exists to satisfy `From<Chromosome>`
+}
+
+impl<'a, I> Scored<'a, I>
+  where I: Individual, {
+
+  fn individual(&self) -> &'a I {
+    match self {
+      Self::Borrowed(individual, _) => individual,
+      Self::Offspring(_) => unreachable!(),
+    }
+  }
+}
+
+impl<I> Individual for Scored<'_, I>
+  where I: Individual, {
+
+  type Gene = I::Gene;
+
+  fn fitness(&self) -> f64 {
+    match self {
+      Self::Borrowed(_, score) => *score,
+      Self::Offspring(_) => 0.0,
+    }
+  }
+
+  fn behaviour(&self) -> Option<Vec<f64>> {
+    match self {
+      Self::Borrowed(individual, _) => individual.behaviour(),
+      Self::Offspring(_) => None,
+    }
+  }
+
+  fn violation(&self) -> f64 {
+    match self {
+      Self::Borrowed(individual, _) => individual.violation(),
+      Self::Offspring(_) => 0.0,
+    }
+  }
+
+  fn objectives(&self) -> Option<Vec<f64>> {
+    match self {
+      Self::Borrowed(individual, _) => individual.objectives(),
+      Self::Offspring(_) => None,
+    }
+  }
+}
+
+impl<I> AsRef<Chromosome<I::Gene>> for Scored<'_, I>
+  where I: Individual, {
+
+  fn as_ref(&self) -> &Chromosome<I::Gene> {
+    match self {
+      Self::Borrowed(individual, _) => individual.as_ref(),
+      Self::Offspring(chromosome) => chromosome,
+    }
+  }
+}
+
+impl<I> From<Chromosome<I::Gene>> for Scored<'_, I>
+  where I: Individual, {
+
+  fn from(chromosome: Chromosome<I::Gene>) -> Self {
+    Self::Offspring(chromosome)
+  }
+}
+
+// index below `len` drawn from the same bits on 32 and 64 bit targets, unlike
+// a `usize` range, so seeded runs replay identically natively and in wasm
+pub(crate) fn gen_index(rng: &mut impl RngCore, len: usize) -> usize {
+  assert!(len <= u32::MAX as usize);
+  rng.gen_range(0..len as u32) as usize
 }
 
 pub trait SelectionMethod {
   fn select<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> &'a I
     where I: Individual;
+
+  // both parents of one child, methods that pair parents up by more than two
+  // independent selections override this
+  fn select_parents<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    [self.select(rng, population), self.select(rng, population)]
+  }
+
+  // hands `breed` a way to draw both parents of one child from `population`,
+  // as often as it takes to breed a generation; methods that convert the
+  // population before selecting from it, like the boxed ones, do so once
+  // here rather than for every child
+  fn with_parents<'a, I, T>(&self, population: &'a [I], breed: impl FnOnce(&dyn Fn(&mut dyn RngCore) -> [&'a I; 2]) -> T) -> T
+    where I: Individual {
+    breed(&|mut rng| self.select_parents(&mut rng, population))
+  }
+
+  // both parents of one child out of a population `prepare` was not called
+  // with, like a steady-state pool or a part of the prepared population;
+  // methods whose state belongs to the prepared population select the way
+  // the method they wrap does
+  fn select_parents_unprepared<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    self.select_parents(rng, population)
+  }
+
+  // sees every evaluated population before parents are selected from it, in
+  // the same order, for methods that keep state across generations; called
+  // by the optimizers, and by `GeneticAlgorithm::prepare` for manual evolves
+  fn prepare<I>(&mut self, _population: &[I])
+    where I: Individual {}
+
+  // whatever the method keeps across generations, saved with the optimizer
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    serde_json::Value::Null
+  }
+
+  // restores what `save_state` returned, on a method configured the same
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, _state: serde_json::Value) -> std::io::Result<()> {
+    Ok(())
+  }
 }
 
 #[derive(Default)]
@@ -34,8 +245,32 @@ impl SelectionMethod for RouletteWheelSelection {
   }
 }
 
-pub trait CrossoverMethod {
-  fn crossover(&self, rng: &mut impl RngCore, parent_a: &Chromosome, parent_b: &Chromosome) -> Chromosome;
+// best of `size` individuals drawn uniformly, with replacement
+#[derive(Clone, Debug)]
+pub struct TournamentSelection {
+  size: usize,
+}
+
+impl TournamentSelection {
+  pub fn new(size: usize) -> Self {
+    assert!(size > 0);
+    Self { size }
+  }
+}
+
+impl SelectionMethod for TournamentSelection {
+  fn select<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+    where I: Individual {
+    assert!(!population.is_empty());
+    (0..self.size)
+      .map(|_| &population[gen_index(rng, population.len())])
+      .max_by(|a, b| a.fitness().total_cmp(&b.fitness()))
+      .expect("Error - tournaments have at least one entrant")
+  }
+}
+
+pub trait CrossoverMethod<G = f64> {
+  fn crossover(&self, rng: &mut impl RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G>;
 }
 
 #[derive(Clone, Debug, Default)]
@@ -47,19 +282,37 @@ impl UniformCrossover {
   }
 }
 
-impl CrossoverMethod for UniformCrossover {
-  fn crossover(&self, rng: &mut impl RngCore, parent_a: &Chromosome, parent_b: &Chromosome) -> Chromosome {
+impl<G> CrossoverMethod<G> for UniformCrossover
+  where G: Gene, {
+
+  fn crossover(&self, rng: &mut impl RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G> {
     assert_eq!(parent_a.len(), parent_b.len());
-    parent_a
-      .iter()
-      .zip(parent_b.iter())
-      .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
-      .collect()
+
+    // step sizes follow their genes, so self-adaptive strategies survive crossover
+    let carry_step_sizes = parent_a.has_step_sizes() && parent_b.has_step_sizes();
+
+    let mut genes = Vec::with_capacity(parent_a.len());
+    let mut step_sizes = Vec::new();
+
+    for i in 0..parent_a.len() {
+      let parent = if rng.gen_bool(0.5) { parent_a } else { parent_b };
+
+      genes.push(parent.genes[i].clone());
+      if carry_step_sizes {
+        step_sizes.push(parent.step_sizes[i]);
+      }
+    }
+
+    Chromosome { genes, step_sizes }
   }
 }
 
-pub trait MutationMethod {
-  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome);
+pub trait MutationMethod<G = f64> {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome<G>);
+
+  // sees the statistics of every generation so far once each is evolved, for
+  // methods whose rates follow the run, like `ScheduledMutation`
+  fn adapt(&mut self, _history: &[Statistics]) {}
 }
 
 #[derive(Clone, Debug)]
@@ -85,36 +338,103 @@ impl MutationMethod for GaussianMutation {
   }
 }
 
+impl AdaptiveMutation for GaussianMutation {
+  fn rates(&self) -> MutationRates {
+    MutationRates::new(self.chance, self.mag)
+  }
+
+  fn set_rates(&mut self, rates: MutationRates) {
+    *self = Self::new(rates.chance, rates.mag);
+  }
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Statistics {
   min_fitness: f64,
   max_fitness: f64,
   avg_fitness: f64,
+  median_fitness: f64,
+  std_dev_fitness: f64,
+  diversity: f64, // mean pairwise distance between chromosomes
+  best_index: usize,
+  percentiles: [f64; PERCENTILES], // every 5th fitness percentile, 0 to 100
 }
 
+const PERCENTILES: usize = 21;
+
 impl Statistics {
-  fn new<I>(population: &[I]) -> Self
+  pub fn new<I>(population: &[I]) -> Self
     where I: Individual {
     assert!(!population.is_empty());
 
     let mut min_fitness = population[0].fitness();
     let mut max_fitness = min_fitness;
     let mut sum_fitness = 0.0;
+    let mut best_index = 0;
 
-    for individual in population {
+    for (index, individual) in population.iter().enumerate() {
       let fitness = individual.fitness();
 
+      if fitness > max_fitness {
+        best_index = index;
+      }
+
       min_fitness = min_fitness.min(fitness);
       max_fitness = max_fitness.max(fitness);
       sum_fitness += fitness;
     }
 
+    let avg_fitness = sum_fitness / (population.len() as f64);
+
+    let variance = population
+      .iter()
+      .map(|individual| (individual.fitness() - avg_fitness).powi(2))
+      .sum::<f64>() / (population.len() as f64);
+
+    let mut sorted_fitnesses: Vec<_> = population
+      .iter()
+      .map(Individual::fitness)
+      .collect();
+    sorted_fitnesses.sort_by(f64::total_cmp);
+
+    // histories keep one of these per generation, so only a handful of
+    // percentiles are kept rather than every fitness
+    let percentiles = std::array::from_fn(|i| {
+      interpolate(&sorted_fitnesses, i as f64 / (PERCENTILES - 1) as f64)
+    });
+
     Self {
       min_fitness,
       max_fitness,
-      avg_fitness: sum_fitness / (population.len() as f64)
+      avg_fitness,
+      median_fitness: interpolate(&sorted_fitnesses, 0.5),
+      std_dev_fitness: variance.sqrt(),
+      diversity: Self::diversity_of(population),
+      best_index,
+      percentiles,
     }
   }
+
+  fn diversity_of<I>(population: &[I]) -> f64
+    where I: Individual {
+    let pairs = population.len() * (population.len() - 1) / 2;
+    if pairs == 0 {
+      return 0.0;
+    }
+
+    let total_distance = population
+      .iter()
+      .enumerate()
+      .flat_map(|(i, a)| {
+        population[i + 1..]
+          .iter()
+          .map(move |b| a.as_ref().distance(b.as_ref()))
+      })
+      .sum::<f64>();
+
+    total_distance / pairs as f64
+  }
 }
 
 impl Statistics {
@@ -129,53 +449,249 @@ impl Statistics {
   pub fn avg_fitness(&self) -> f64 {
     self.avg_fitness
   }
+
+  pub fn median_fitness(&self) -> f64 {
+    self.median_fitness
+  }
+
+  pub fn std_dev_fitness(&self) -> f64 {
+    self.std_dev_fitness
+  }
+
+  pub fn diversity(&self) -> f64 {
+    self.diversity
+  }
+
+  pub fn best_index(&self) -> usize {
+    self.best_index
+  }
+
+  // fitness percentile, p in 0..=100; exact at multiples of 5, linearly
+  // interpolated between them
+  pub fn percentile(&self, p: f64) -> f64 {
+    assert!((0.0..=100.0).contains(&p));
+
+    interpolate(&self.percentiles, p / 100.0)
+  }
+}
+
+// value at `q` (0..=1) along sorted `values`, linearly interpolated
+fn interpolate(values: &[f64], q: f64) -> f64 {
+  let rank = q * (values.len() - 1) as f64;
+  let lo = rank.floor() as usize;
+  let hi = rank.ceil() as usize;
+  let t = rank - lo as f64;
+
+  values[lo] * (1.0 - t) + values[hi] * t
 }
 
 pub struct GeneticAlgorithm<S, C, M> {
   selection_method: S,
   crossover_method: C,
   mutation_method: M,
+  fitness_scaling: FitnessScaling,
+  penalty: Penalty,
 }
 
 impl<S, C, M> GeneticAlgorithm<S, C, M> 
-  where S: SelectionMethod,
-        C: CrossoverMethod,
-        M: MutationMethod, {
+  where S: SelectionMethod, {
 
   pub fn new(selection_method: S, crossover_method: C, mutation_method: M) -> Self {
     Self { 
       selection_method,
       crossover_method,
       mutation_method,
+      fitness_scaling: FitnessScaling::Identity,
+      penalty: Penalty::None,
     }
   }
 
-  pub fn evolve<'a, I: Individual>(&'a self, rng: &'a mut impl RngCore, population: &'a [I]) -> (impl Iterator<Item = I> + '_, Statistics) {
-    assert!(!population.is_empty());
+  pub fn with_fitness_scaling(mut self, fitness_scaling: FitnessScaling) -> Self {
+    self.fitness_scaling = fitness_scaling;
+    self
+  }
 
-    let new_population = (0..population.len())
-      .map(|_| {
-        // selection
-        let parent_a = self.selection_method
-          .select(rng, population)
-          .as_ref();
-        let parent_b = self.selection_method
-          .select(rng, population)
-          .as_ref();
+  pub fn fitness_scaling(&self) -> FitnessScaling {
+    self.fitness_scaling
+  }
+
+  pub fn with_penalty(mut self, penalty: Penalty) -> Self {
+    self.penalty = penalty;
+    self
+  }
 
-        // crossover
-        let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+  pub fn penalty(&self) -> Penalty {
+    self.penalty
+  }
 
-        // mutation
-        self.mutation_method.mutate(rng, &mut child);
+  pub fn evolve<'a, I>(&'a self, rng: &'a mut impl RngCore, population: &'a [I]) -> (impl Iterator<Item = I> + '_, Statistics)
+    where I: Individual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    assert!(!population.is_empty());
 
-        I::from(child)
-      });
+    let scaled = self.scaled(population);
+    let new_population = self.selection_method
+      .with_parents(&scaled, |select| {
+        (0..population.len())
+          .map(|_| self.breed(rng, select, &mut (), &mut ()))
+          .collect::<Vec<_>>()
+      })
+      .into_iter();
 
     let stats = Statistics::new(population);
     
     (new_population, stats)
   }
+
+  // like `evolve`, reporting every selection and offspring to `observer`
+  pub fn evolve_observed<I>(&self, rng: &mut impl RngCore, population: &[I], observer: &mut dyn Observer<I::Gene>) -> (Vec<I>, Statistics)
+    where I: Individual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    self.evolve_with(rng, population, observer, &mut ())
+  }
+
+  // like `evolve_observed`, handing every child and its parents to `hook`
+  pub fn evolve_with<I>(
+    &self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn Observer<I::Gene>,
+    hook: &mut dyn BreedHook<I>,
+  ) -> (Vec<I>, Statistics)
+    where I: Individual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    assert!(!population.is_empty());
+
+    let scaled = self.scaled(population);
+    let new_population = self.selection_method.with_parents(&scaled, |select| {
+      (0..population.len())
+        .map(|_| self.breed(rng, select, observer, hook))
+        .collect()
+    });
+
+    let stats = Statistics::new(population);
+
+    (new_population, stats)
+  }
+
+  // let the selection method look at `population` before breeding from it,
+  // which `evolve` and its variants leave to the caller
+  pub fn prepare<I>(&mut self, population: &[I])
+    where I: Individual, {
+    self.selection_method.prepare(population);
+  }
+
+  // two parents out of `population`, picked the way this algorithm picks them
+  // from populations it was not prepared with
+  pub fn parents<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual, {
+    assert!(!population.is_empty());
+
+    let scaled = self.scaled(population);
+    self.selection_method
+      .select_parents_unprepared(rng, &scaled)
+      .map(Scored::individual)
+  }
+
+  // a child of `parent_a` and `parent_b`, crossed over and mutated
+  pub fn recombine<G>(&self, rng: &mut impl RngCore, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) -> Chromosome<G>
+    where C: CrossoverMethod<G>,
+          M: MutationMethod<G>, {
+    let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+    self.mutation_method.mutate(rng, &mut child);
+    child
+  }
+
+  // the population as selection sees it, under penalized and scaled fitness
+  fn scaled<'a, I: Individual>(&self, population: &'a [I]) -> Vec<Scored<'a, I>> {
+    let fitnesses: Vec<_> = population
+      .iter()
+      .map(|individual| self.penalty.apply(individual.fitness(), individual.violation()))
+      .collect();
+
+    population
+      .iter()
+      .zip(self.fitness_scaling.scale(&fitnesses))
+      .map(|(individual, fitness)| Scored::Borrowed(individual, fitness))
+      .collect()
+  }
+
+  fn breed<'s, 'a, I>(
+    &self,
+    rng: &mut impl RngCore,
+    select: &dyn Fn(&mut dyn RngCore) -> [&'s Scored<'a, I>; 2],
+    observer: &mut dyn Observer<I::Gene>,
+    hook: &mut dyn BreedHook<I>,
+  ) -> I
+    where I: Individual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    let birth = self.conceive(rng, select);
+    Self::deliver(rng, birth, observer, hook)
+  }
+
+  // selection, crossover and mutation, which need nothing but `rng`
+  fn conceive<'s, 'a, I>(&self, rng: &mut impl RngCore, select: &dyn Fn(&mut dyn RngCore) -> [&'s Scored<'a, I>; 2]) -> Birth<'a, I>
+    where I: Individual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    // selection
+    let parents = select(rng).map(Scored::individual);
+
+    // crossover
+    let crossed = self.crossover_method.crossover(rng, parents[0].as_ref(), parents[1].as_ref());
+
+    // mutation
+    let mut child = crossed.clone();
+    self.mutation_method.mutate(rng, &mut child);
+
+    Birth { parents, crossed, child }
+  }
+
+  // reporting the child and handing it over, in breeding order
+  fn deliver<I>(rng: &mut impl RngCore, birth: Birth<'_, I>, observer: &mut dyn Observer<I::Gene>, hook: &mut dyn BreedHook<I>) -> I
+    where I: Individual, {
+    let Birth { parents, crossed, child } = birth;
+
+    observer.after_selection(parents[0].as_ref(), parents[1].as_ref());
+    observer.after_offspring(&child);
+
+    let mut child = I::from(child);
+    hook.bred(rng, parents, &crossed, &mut child);
+    child
+  }
+}
+
+// a bred child before anyone saw it
+struct Birth<'a, I: Individual> {
+  parents: [&'a I; 2],
+  crossed: Chromosome<I::Gene>, // before mutation
+  child: Chromosome<I::Gene>,
+}
+
+impl<S, C, M> GeneticAlgorithm<SpeciatedSelection<S>, C, M>
+  where S: SelectionMethod, {
+
+  pub fn species(&self) -> &[Species] {
+    self.selection_method.species()
+  }
+}
+
+impl<S, C, M> GeneticAlgorithm<NoveltySelection<S>, C, M>
+  where S: SelectionMethod, {
+
+  pub fn archive(&self) -> &NoveltyArchive {
+    self.selection_method.archive()
+  }
+}
+
+impl<C, M> GeneticAlgorithm<Nsga2Selection, C, M> {
+  pub fn pareto_front(&self) -> &[usize] {
+    self.selection_method.pareto_front()
+  }
 }
 
 pub type DefaultGeneticAlgorithm = GeneticAlgorithm<
@@ -184,54 +700,165 @@ pub type DefaultGeneticAlgorithm = GeneticAlgorithm<
   GaussianMutation,
 >;
 
+// keep in line with `GeneticAlgorithmConfig::default`
 impl Default for DefaultGeneticAlgorithm {
   fn default() -> Self {
     Self {
       selection_method: RouletteWheelSelection::new(),
       crossover_method: UniformCrossover::new(),
       mutation_method: GaussianMutation::new(0.015, 0.3),
+      fitness_scaling: FitnessScaling::Identity,
+      penalty: Penalty::None,
     }
   }
 }
 
-#[derive(Clone, Debug)]
-pub struct Chromosome {
-  genes: Vec<f64>
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chromosome<G = f64> {
+  genes: Vec<G>,
+  step_sizes: Vec<f64>, // per-gene strategy parameters, empty unless self-adaptive
 }
 
-impl Chromosome {
+impl<G> Chromosome<G>
+  where G: Gene, {
+
+  // the genes as real numbers (`Gene::value`), step sizes included
+  pub fn values(&self) -> Chromosome {
+    Chromosome {
+      genes: self.genes.iter().map(Gene::value).collect(),
+      step_sizes: self.step_sizes.clone(),
+    }
+  }
+
+  pub fn with_step_sizes(mut self, step_sizes: Vec<f64>) -> Self {
+    assert_eq!(self.genes.len(), step_sizes.len());
+    self.step_sizes = step_sizes;
+    self
+  }
+
   pub fn len(&self) -> usize {
     self.genes.len()
   }
 
-  pub fn iter(&self) -> impl Iterator<Item = &f64> {
+  // euclidean distance between the genes of two chromosomes
+  pub fn distance(&self, other: &Chromosome<G>) -> f64 {
+    assert_eq!(self.len(), other.len());
+    self.genes
+      .iter()
+      .zip(other.genes.iter())
+      .map(|(a, b)| a.difference(b).powi(2))
+      .sum::<f64>()
+      .sqrt()
+  }
+
+  pub fn step_sizes(&self) -> &[f64] {
+    &self.step_sizes
+  }
+
+  pub fn has_step_sizes(&self) -> bool {
+    self.step_sizes.len() == self.genes.len()
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = &G> {
     self.genes.iter()
   }
 
-  pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f64> {
+  pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut G> {
     self.genes.iter_mut()
   }
 }
 
-impl Index<usize> for Chromosome {
-  type Output = f64;
+impl<G> Index<usize> for Chromosome<G> {
+  type Output = G;
 
   fn index(&self, index: usize) -> &Self::Output {
     &self.genes[index]
   }
 }
 
-impl FromIterator<f64> for Chromosome {
-  fn from_iter<T: IntoIterator<Item = f64>>(iter: T) -> Self {
-    Self { genes: iter.into_iter().collect() }
+impl<G> FromIterator<G> for Chromosome<G> {
+  fn from_iter<T: IntoIterator<Item = G>>(iter: T) -> Self {
+    Self { 
+      genes: iter.into_iter().collect(),
+      step_sizes: Vec::new(),
+    }
   }
 }
 
-impl IntoIterator for Chromosome {
-  type Item = f64;
-  type IntoIter = impl Iterator<Item = f64>; // #![feature(impl_trait_in_assoc_type)]
+impl<G> IntoIterator for Chromosome<G> {
+  type Item = G;
+  type IntoIter = impl Iterator<Item = G>; // #![feature(impl_trait_in_assoc_type)]
 
   fn into_iter(self) -> Self::IntoIter {
     self.genes.into_iter()
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::TestIndividual;
+
+  fn statistics(fitnesses: &[f64]) -> Statistics {
+    let population: Vec<_> = fitnesses
+      .iter()
+      .map(|&fitness| TestIndividual::new(&[fitness], fitness))
+      .collect();
+    Statistics::new(&population)
+  }
+
+  #[test]
+  fn summarizes_fitness() {
+    let stats = statistics(&[4.0, 1.0, 3.0, 2.0]);
+
+    assert_eq!(stats.min_fitness(), 1.0);
+    assert_eq!(stats.max_fitness(), 4.0);
+    assert_eq!(stats.avg_fitness(), 2.5);
+    assert_eq!(stats.median_fitness(), 2.5);
+    assert_eq!(stats.std_dev_fitness(), 1.25f64.sqrt());
+    assert_eq!(stats.best_index(), 0);
+  }
+
+  #[test]
+  fn interpolates_percentiles() {
+    let stats = statistics(&[10.0, 0.0, 20.0]);
+
+    assert_eq!(stats.percentile(0.0), 0.0);
+    assert_eq!(stats.percentile(25.0), 5.0);
+    assert_eq!(stats.percentile(50.0), 10.0);
+    assert_eq!(stats.percentile(100.0), 20.0);
+  }
+
+  #[test]
+  fn keeps_percentiles_rather_than_every_fitness() {
+    let fitnesses: Vec<_> = (0..=1000).map(f64::from).collect();
+    let stats = statistics(&fitnesses);
+
+    assert_eq!(stats.percentile(10.0), 100.0);
+    assert_eq!(stats.percentile(90.0), 900.0);
+    assert_eq!(stats.percentile(12.5), 125.0);
+    assert_eq!(stats.median_fitness(), 500.0);
+  }
+
+  #[test]
+  fn single_individual() {
+    let stats = statistics(&[3.0]);
+
+    assert_eq!(stats.median_fitness(), 3.0);
+    assert_eq!(stats.std_dev_fitness(), 0.0);
+    assert_eq!(stats.diversity(), 0.0);
+  }
+
+  #[test]
+  fn diversity_is_the_mean_pairwise_distance() {
+    // genes 0, 3 and 4: distances 3, 4 and 1
+    let stats = statistics(&[0.0, 3.0, 4.0]);
+    assert!((stats.diversity() - 8.0 / 3.0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn first_of_tied_best_wins() {
+    assert_eq!(statistics(&[1.0, 5.0, 5.0, 2.0]).best_index(), 1);
+  }
+}
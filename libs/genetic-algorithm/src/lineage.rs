@@ -0,0 +1,246 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::*;
+
+// individuals that can carry the id the lineage log hands out
+pub trait TrackedIndividual: Individual {
+  fn id(&self) -> u64;
+  fn set_id(&mut self, id: u64);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineageEvent {
+  Crossover { first_parent_genes: usize }, // genes matching the first parent
+  Mutation { genes: usize }, // genes changed by mutation
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineageRecord {
+  pub id: u64,
+  pub generation: usize,
+  pub parents: Vec<u64>, // empty for founders
+  pub events: Vec<LineageEvent>,
+}
+
+// every individual ever tracked, ids are handed out in order so an id is also
+// its record's index
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Lineage {
+  generation: usize,
+  records: Vec<LineageRecord>,
+}
+
+impl Lineage {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn generation(&self) -> usize {
+    self.generation
+  }
+
+  pub fn len(&self) -> usize {
+    self.records.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.records.is_empty()
+  }
+
+  pub fn records(&self) -> &[LineageRecord] {
+    &self.records
+  }
+
+  pub fn record(&self, id: u64) -> Option<&LineageRecord> {
+    self.records.get(id as usize)
+  }
+
+  // record an individual without parents, such as a random one
+  pub fn founder(&mut self) -> u64 {
+    self.push(Vec::new(), Vec::new())
+  }
+
+  pub fn founders<I>(&mut self, population: &mut [I])
+    where I: TrackedIndividual {
+    for individual in population {
+      individual.set_id(self.founder());
+    }
+  }
+
+  // start recording the children of the current generation
+  pub fn advance(&mut self) {
+    self.generation += 1;
+  }
+
+  pub fn push(&mut self, parents: Vec<u64>, events: Vec<LineageEvent>) -> u64 {
+    let id = self.records.len() as u64;
+
+    self.records.push(LineageRecord {
+      id,
+      generation: self.generation,
+      parents,
+      events,
+    });
+
+    id
+  }
+
+  // every distinct ancestor of `id`, in id order
+  pub fn ancestors(&self, id: u64) -> Vec<u64> {
+    let mut ancestors = BTreeSet::new();
+    let mut queue = VecDeque::from([id]);
+
+    while let Some(id) = queue.pop_front() {
+      for &parent in self.record(id).map(|record| record.parents.as_slice()).unwrap_or_default() {
+        if ancestors.insert(parent) {
+          queue.push_back(parent);
+        }
+      }
+    }
+
+    ancestors.into_iter().collect()
+  }
+
+  // number of distinct individuals descending from `id`
+  pub fn descendants_count(&self, id: u64) -> usize {
+    // children always come after their parents
+    let mut descends = vec![false; self.records.len()];
+
+    self.records
+      .iter()
+      .skip(id as usize + 1)
+      .filter(|record| {
+        let is_descendant = record.parents
+          .iter()
+          .any(|&parent| parent == id || descends[parent as usize]);
+
+        descends[record.id as usize] = is_descendant;
+        is_descendant
+      })
+      .count()
+  }
+
+  #[cfg(feature = "serde")]
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("Error - lineage is always serializable")
+  }
+}
+
+// records every child with its parents, and what crossover and mutation did
+impl<I> BreedHook<I> for Lineage
+  where I: TrackedIndividual, {
+
+  fn bred(&mut self, _rng: &mut dyn RngCore, [parent_a, parent_b]: [&I; 2], crossed: &Chromosome<I::Gene>, child: &mut I) {
+    let first_parent_genes = crossed
+      .iter()
+      .zip(parent_a.as_ref().iter())
+      .filter(|(a, b)| a == b)
+      .count();
+    let genes = crossed
+      .iter()
+      .zip(child.as_ref().iter())
+      .filter(|(a, b)| a != b)
+      .count();
+
+    let id = self.push(
+      vec![parent_a.id(), parent_b.id()],
+      vec![
+        LineageEvent::Crossover { first_parent_genes },
+        LineageEvent::Mutation { genes },
+      ],
+    );
+    child.set_id(id);
+  }
+
+  fn founded(&mut self, _rng: &mut dyn RngCore, child: &mut I) {
+    child.set_id(self.founder());
+  }
+}
+
+impl<S, C, M> GeneticAlgorithm<S, C, M>
+  where S: SelectionMethod, {
+
+  // like `evolve`, also recording every child's parents and events in `lineage`
+  pub fn evolve_with_lineage<I>(&self, rng: &mut impl RngCore, population: &[I], lineage: &mut Lineage) -> (Vec<I>, Statistics)
+    where I: TrackedIndividual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    self.evolve_with_lineage_observed(rng, population, lineage, &mut ())
+  }
+
+  // like `evolve_with_lineage`, reporting every selection and offspring to `observer`
+  pub fn evolve_with_lineage_observed<I>(
+    &self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    lineage: &mut Lineage,
+    observer: &mut dyn Observer<I::Gene>,
+  ) -> (Vec<I>, Statistics)
+    where I: TrackedIndividual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    lineage.advance();
+    self.evolve_with(rng, population, observer, lineage)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  // 0 and 1 found the line, 2 is their child, 3 a child of 2 and 1
+  fn family() -> Lineage {
+    let mut lineage = Lineage::new();
+    lineage.founder();
+    lineage.founder();
+    lineage.advance();
+    lineage.push(vec![0, 1], Vec::new());
+    lineage.advance();
+    lineage.push(vec![2, 1], Vec::new());
+    lineage
+  }
+
+  #[test]
+  fn ancestors() {
+    let lineage = family();
+
+    assert_eq!(lineage.ancestors(0), Vec::<u64>::new());
+    assert_eq!(lineage.ancestors(2), vec![0, 1]);
+    assert_eq!(lineage.ancestors(3), vec![0, 1, 2]);
+  }
+
+  #[test]
+  fn descendants_count() {
+    let lineage = family();
+
+    assert_eq!(lineage.descendants_count(0), 2);
+    assert_eq!(lineage.descendants_count(1), 2);
+    assert_eq!(lineage.descendants_count(2), 1);
+    assert_eq!(lineage.descendants_count(3), 0);
+  }
+
+  #[test]
+  fn breeding_records_parents() {
+    let mut rng = testing::rng();
+    let mut lineage = Lineage::new();
+    let mut population = vec![
+      TestIndividual::new(&[0.0, 0.0], 1.0),
+      TestIndividual::new(&[1.0, 1.0], 2.0),
+    ];
+    lineage.founders(&mut population);
+
+    let (children, _) = DefaultGeneticAlgorithm::default().evolve_with_lineage(&mut rng, &population, &mut lineage);
+
+    assert_eq!(lineage.len(), 4);
+    for child in &children {
+      let record = lineage.record(child.id).unwrap();
+      assert_eq!(record.generation, 1);
+      assert_eq!(record.parents.len(), 2);
+      assert!(record.parents.iter().all(|&parent| parent < 2));
+    }
+  }
+}
@@ -0,0 +1,332 @@
+use rand::Rng;
+use rand::RngCore;
+use rand_distr::{Cauchy, Distribution, StandardNormal};
+
+use crate::*;
+
+#[derive(Clone, Debug)]
+pub struct NormalMutation {
+  chance: f64, // chance of change
+  sigma: f64, // standard deviation of change
+}
+
+impl NormalMutation {
+  pub fn new(chance: f64, sigma: f64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    assert!(sigma >= 0.0);
+    Self { chance, sigma }
+  }
+}
+
+impl MutationMethod for NormalMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    for gene in child.iter_mut() {
+      if rng.gen_bool(self.chance) {
+        *gene += self.sigma * rng.sample::<f64, _>(StandardNormal);
+      }
+    }
+  }
+}
+
+impl AdaptiveMutation for NormalMutation {
+  fn rates(&self) -> MutationRates {
+    MutationRates::new(self.chance, self.sigma)
+  }
+
+  fn set_rates(&mut self, rates: MutationRates) {
+    *self = Self::new(rates.chance, rates.mag);
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct CauchyMutation {
+  chance: f64, // chance of change
+  scale: f64, // half width at half maximum of change
+}
+
+impl CauchyMutation {
+  pub fn new(chance: f64, scale: f64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    assert!(scale > 0.0);
+    Self { chance, scale }
+  }
+}
+
+impl MutationMethod for CauchyMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    let cauchy = Cauchy::new(0.0, self.scale).expect("Error - invalid cauchy scale");
+
+    for gene in child.iter_mut() {
+      if rng.gen_bool(self.chance) {
+        *gene += cauchy.sample(rng);
+      }
+    }
+  }
+}
+
+impl AdaptiveMutation for CauchyMutation {
+  fn rates(&self) -> MutationRates {
+    MutationRates::new(self.chance, self.scale)
+  }
+
+  fn set_rates(&mut self, rates: MutationRates) {
+    *self = Self::new(rates.chance, rates.mag);
+  }
+}
+
+// deb's bounded polynomial mutation
+#[derive(Clone, Debug)]
+pub struct PolynomialMutation {
+  chance: f64, // chance of change
+  eta: f64, // distribution index, larger keeps children closer to parents
+  min: f64,
+  max: f64,
+}
+
+impl PolynomialMutation {
+  pub fn new(chance: f64, eta: f64, min: f64, max: f64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    assert!(eta >= 0.0);
+    assert!(min < max);
+    Self { chance, eta, min, max }
+  }
+}
+
+impl MutationMethod for PolynomialMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    let range = self.max - self.min;
+    let power = 1.0 / (self.eta + 1.0);
+
+    for gene in child.iter_mut() {
+      if !rng.gen_bool(self.chance) {
+        continue;
+      }
+
+      let y = gene.clamp(self.min, self.max);
+      let delta_lo = (y - self.min) / range;
+      let delta_hi = (self.max - y) / range;
+      let u = rng.gen::<f64>();
+
+      let delta = if u < 0.5 {
+        let xy = 1.0 - delta_lo;
+        let val = 2.0 * u + (1.0 - 2.0 * u) * xy.powf(self.eta + 1.0);
+        val.powf(power) - 1.0
+      } else {
+        let xy = 1.0 - delta_hi;
+        let val = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * xy.powf(self.eta + 1.0);
+        1.0 - val.powf(power)
+      };
+
+      *gene = (y + delta * range).clamp(self.min, self.max);
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResetMutation {
+  chance: f64, // chance of change
+  min: f64,
+  max: f64,
+}
+
+impl ResetMutation {
+  pub fn new(chance: f64, min: f64, max: f64) -> Self {
+    assert!((0.0..=1.0).contains(&chance));
+    assert!(min <= max);
+    Self { chance, min, max }
+  }
+}
+
+impl MutationMethod for ResetMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    for gene in child.iter_mut() {
+      if rng.gen_bool(self.chance) {
+        *gene = rng.gen_range(self.min..=self.max);
+      }
+    }
+  }
+}
+
+// evolution strategy style mutation, every gene carries its own step size
+// that is mutated log-normally before being used to perturb the gene
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveMutation {
+  initial_step_size: f64,
+  min_step_size: f64,
+}
+
+impl SelfAdaptiveMutation {
+  pub fn new(initial_step_size: f64, min_step_size: f64) -> Self {
+    assert!(initial_step_size > 0.0);
+    assert!(min_step_size >= 0.0);
+    Self { initial_step_size, min_step_size }
+  }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    if child.genes.is_empty() {
+      return;
+    }
+
+    if !child.has_step_sizes() {
+      child.step_sizes = vec![self.initial_step_size; child.len()];
+    }
+
+    // learning rates recommended by schwefel
+    let n = child.len() as f64;
+    let tau_global = 1.0 / (2.0 * n).sqrt();
+    let tau_local = 1.0 / (2.0 * n.sqrt()).sqrt();
+
+    let global = tau_global * rng.sample::<f64, _>(StandardNormal);
+
+    for (gene, step_size) in child.genes.iter_mut().zip(child.step_sizes.iter_mut()) {
+      let local = tau_local * rng.sample::<f64, _>(StandardNormal);
+
+      *step_size = (*step_size * (global + local).exp()).max(self.min_step_size);
+      *gene += *step_size * rng.sample::<f64, _>(StandardNormal);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::rng;
+
+  fn chromosome(genes: &[f64]) -> Chromosome {
+    genes.iter().copied().collect()
+  }
+
+  fn mutated(method: &impl MutationMethod, genes: &[f64]) -> Chromosome {
+    let mut child = chromosome(genes);
+    method.mutate(&mut rng(), &mut child);
+    child
+  }
+
+  fn changed(method: &impl MutationMethod) -> usize {
+    let genes = [0.5; 100];
+    let child = mutated(method, &genes);
+    child.iter().zip(&genes).filter(|(a, b)| a != b).count()
+  }
+
+  #[test]
+  fn chance_zero_leaves_every_gene_alone() {
+    assert_eq!(changed(&NormalMutation::new(0.0, 1.0)), 0);
+    assert_eq!(changed(&CauchyMutation::new(0.0, 1.0)), 0);
+    assert_eq!(changed(&PolynomialMutation::new(0.0, 20.0, -1.0, 1.0)), 0);
+    assert_eq!(changed(&ResetMutation::new(0.0, -1.0, 1.0)), 0);
+  }
+
+  #[test]
+  fn chance_one_changes_every_gene() {
+    assert_eq!(changed(&NormalMutation::new(1.0, 1.0)), 100);
+    assert_eq!(changed(&CauchyMutation::new(1.0, 1.0)), 100);
+    assert_eq!(changed(&PolynomialMutation::new(1.0, 20.0, -1.0, 1.0)), 100);
+    assert_eq!(changed(&ResetMutation::new(1.0, -1.0, 1.0)), 100);
+  }
+
+  #[test]
+  fn normal_changes_scale_with_sigma() {
+    let mean_change = |sigma| {
+      let child = mutated(&NormalMutation::new(1.0, sigma), &[0.0; 1000]);
+      child.iter().map(|gene| gene.abs()).sum::<f64>() / 1000.0
+    };
+
+    // mean absolute deviation of a normal distribution is sigma * sqrt(2 / pi)
+    let expected = (2.0 / std::f64::consts::PI).sqrt();
+    assert!((mean_change(1.0) - expected).abs() < 0.1);
+    assert!((mean_change(0.1) - 0.1 * expected).abs() < 0.01);
+    assert_eq!(mean_change(0.0), 0.0);
+  }
+
+  #[test]
+  fn polynomial_stays_within_bounds() {
+    let mut rng = rng();
+    let method = PolynomialMutation::new(1.0, 0.5, -1.0, 1.0);
+
+    // genes on, within, and outside of the bounds
+    let mut child = chromosome(&[-1.0, -0.99, 0.0, 0.99, 1.0, -5.0, 5.0]);
+
+    for _ in 0..1000 {
+      method.mutate(&mut rng, &mut child);
+      assert!(child.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+    }
+  }
+
+  #[test]
+  fn polynomial_keeps_children_closer_with_a_larger_eta() {
+    let mean_change = |eta| {
+      let child = mutated(&PolynomialMutation::new(1.0, eta, -1.0, 1.0), &[0.0; 1000]);
+      child.iter().map(|gene| gene.abs()).sum::<f64>() / 1000.0
+    };
+
+    assert!(mean_change(100.0) < mean_change(1.0));
+  }
+
+  #[test]
+  fn reset_stays_within_bounds() {
+    let mut rng = rng();
+    let method = ResetMutation::new(1.0, 2.0, 3.0);
+    let mut child = chromosome(&[-10.0, 0.0, 10.0]);
+
+    for _ in 0..1000 {
+      method.mutate(&mut rng, &mut child);
+      assert!(child.iter().all(|gene| (2.0..=3.0).contains(gene)));
+    }
+
+    let child = mutated(&ResetMutation::new(1.0, 4.0, 4.0), &[0.0; 3]);
+    assert!(child.iter().all(|&gene| gene == 4.0));
+  }
+
+  #[test]
+  fn self_adaptive_starts_from_the_initial_step_size() {
+    let child = mutated(&SelfAdaptiveMutation::new(0.5, 0.0), &[0.0; 4]);
+
+    assert_eq!(child.step_sizes().len(), 4);
+    assert!(child.step_sizes().iter().all(|&step_size| step_size > 0.0 && step_size != 0.5));
+    assert!(child.iter().all(|&gene| gene != 0.0));
+  }
+
+  #[test]
+  fn self_adaptive_step_sizes_never_go_below_the_floor() {
+    let mut rng = rng();
+    let method = SelfAdaptiveMutation::new(0.1, 0.05);
+    let mut child = chromosome(&[0.0; 10]);
+
+    for _ in 0..1000 {
+      method.mutate(&mut rng, &mut child);
+      assert!(child.step_sizes().iter().all(|&step_size| step_size >= 0.05));
+    }
+
+    // starting below the floor lifts onto it at the latest after one mutation
+    let mut child = chromosome(&[0.0; 10]).with_step_sizes(vec![1e-9; 10]);
+    method.mutate(&mut rng, &mut child);
+    assert!(child.step_sizes().iter().all(|&step_size| step_size >= 0.05));
+  }
+
+  #[test]
+  fn self_adaptive_step_sizes_update_log_normally() {
+    let mut rng = rng();
+    let method = SelfAdaptiveMutation::new(1.0, 0.0);
+    let n = 10;
+
+    let log_ratios: Vec<_> = (0..1000)
+      .flat_map(|_| {
+        let mut child = chromosome(&vec![0.0; n]).with_step_sizes(vec![1.0; n]);
+        method.mutate(&mut rng, &mut child);
+        child.step_sizes().to_vec()
+      })
+      .map(f64::ln)
+      .collect();
+
+    // the log of every new step size is normal around the old one, with the
+    // global and local learning rates adding up in its variance
+    let mean = log_ratios.iter().sum::<f64>() / log_ratios.len() as f64;
+    let variance = log_ratios.iter().map(|ratio| (ratio - mean).powi(2)).sum::<f64>() / log_ratios.len() as f64;
+    let expected = 1.0 / (2.0 * n as f64) + 1.0 / (2.0 * (n as f64).sqrt());
+
+    assert!(mean.abs() < 0.05);
+    assert!((variance - expected).abs() < 0.1 * expected);
+  }
+}
@@ -0,0 +1,352 @@
+use std::collections::VecDeque;
+
+use crate::*;
+
+// past behaviours that were novel enough to remember
+#[derive(Clone, Debug)]
+pub struct NoveltyArchive {
+  behaviours: VecDeque<Vec<f64>>,
+  k: usize, // nearest neighbours averaged into a novelty score
+  threshold: f64, // min novelty for a behaviour to be archived
+  capacity: usize, // oldest behaviours are forgotten past this
+}
+
+impl NoveltyArchive {
+  pub fn new(k: usize, threshold: f64, capacity: usize) -> Self {
+    assert!(k > 0);
+    assert!(capacity > 0);
+
+    Self {
+      behaviours: VecDeque::new(),
+      k,
+      threshold,
+      capacity,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.behaviours.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.behaviours.is_empty()
+  }
+
+  pub fn behaviours(&self) -> impl Iterator<Item = &[f64]> {
+    self.behaviours.iter().map(Vec::as_slice)
+  }
+
+  // mean distance from every behaviour to its k nearest neighbours among the
+  // other behaviours and the archive
+  pub fn novelty(&self, behaviours: &[Vec<f64>]) -> Vec<f64> {
+    behaviours
+      .iter()
+      .enumerate()
+      .map(|(i, behaviour)| {
+        let mut distances: Vec<_> = behaviours
+          .iter()
+          .enumerate()
+          .filter(|&(j, _)| i != j)
+          .map(|(_, other)| other)
+          .chain(self.behaviours.iter())
+          .map(|other| distance(behaviour, other))
+          .collect();
+
+        if distances.is_empty() {
+          return 0.0;
+        }
+
+        let k = self.k.min(distances.len());
+        distances.select_nth_unstable_by(k - 1, f64::total_cmp);
+
+        distances[..k].iter().sum::<f64>() / k as f64
+      })
+      .collect()
+  }
+
+  fn extend(&mut self, behaviours: &[Vec<f64>], novelty: &[f64]) {
+    let novel = behaviours
+      .iter()
+      .zip(novelty)
+      .filter(|&(_, &novelty)| novelty > self.threshold)
+      .map(|(behaviour, _)| behaviour.clone());
+
+    self.behaviours.extend(novel);
+
+    while self.behaviours.len() > self.capacity {
+      self.behaviours.pop_front();
+    }
+  }
+}
+
+fn distance(a: &[f64], b: &[f64]) -> f64 {
+  assert_eq!(a.len(), b.len());
+  a.iter()
+    .zip(b)
+    .map(|(a, b)| (a - b).powi(2))
+    .sum::<f64>()
+    .sqrt()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum NoveltyObjective {
+  Fitness,
+  Novelty,
+  Mixed { novelty_weight: f64 }, // both normalized to 0..=1 before weighting
+}
+
+// selects by novelty, fitness or a weighted mix of both using the wrapped method
+#[derive(Clone, Debug)]
+pub struct NoveltySelection<S> {
+  selection_method: S,
+  objective: NoveltyObjective,
+  archive: NoveltyArchive,
+  scores: Vec<f64>,
+}
+
+impl<S> NoveltySelection<S>
+  where S: SelectionMethod, {
+
+  pub fn new(selection_method: S, objective: NoveltyObjective, archive: NoveltyArchive) -> Self {
+    if let NoveltyObjective::Mixed { novelty_weight } = objective {
+      assert!((0.0..=1.0).contains(&novelty_weight));
+    }
+
+    Self {
+      selection_method,
+      objective,
+      archive,
+      scores: Vec::new(),
+    }
+  }
+
+  pub fn archive(&self) -> &NoveltyArchive {
+    &self.archive
+  }
+
+  // novelty or mixed score of every individual in the last evaluated
+  // population, empty when selecting by fitness alone
+  pub fn scores(&self) -> &[f64] {
+    &self.scores
+  }
+
+  // scores go through the same clamping and uniform fallback as fitness, so
+  // a population that all behaves alike is selected from uniformly
+  fn evaluate<I>(&mut self, population: &[I])
+    where I: Individual {
+    let novelty_weight = match self.objective {
+      NoveltyObjective::Fitness => {
+        self.scores.clear();
+        return;
+      }
+      NoveltyObjective::Novelty => None,
+      NoveltyObjective::Mixed { novelty_weight } => Some(novelty_weight),
+    };
+
+    let behaviours: Vec<_> = population
+      .iter()
+      .map(|individual| individual.behaviour().expect("Error - individual has no behaviour"))
+      .collect();
+
+    let novelty = self.archive.novelty(&behaviours);
+
+    let scores = match novelty_weight {
+      None => novelty.clone(),
+      Some(novelty_weight) => {
+        let fitness: Vec<_> = population.iter().map(Individual::fitness).collect();
+
+        normalize(&fitness)
+          .zip(normalize(&novelty))
+          .map(|(fitness, novelty)| (1.0 - novelty_weight) * fitness + novelty_weight * novelty)
+          .collect()
+      }
+    };
+
+    self.scores = FitnessScaling::Identity.scale(&scores);
+    self.archive.extend(&behaviours, &novelty);
+  }
+}
+
+fn normalize(values: &[f64]) -> impl Iterator<Item = f64> + '_ {
+  let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+  let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+  let range = max - min;
+
+  values
+    .iter()
+    .map(move |value| if range > 0.0 { (value - min) / range } else { 1.0 })
+}
+
+impl<S> SelectionMethod for NoveltySelection<S>
+  where S: SelectionMethod, {
+
+  fn select<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+    where I: Individual {
+    self.select_parents(rng, population)[0]
+  }
+
+  // by fitness when selecting by fitness or until prepared
+  fn select_parents<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    if self.scores.is_empty() {
+      return self.selection_method.select_parents(rng, population);
+    }
+
+    assert_eq!(population.len(), self.scores.len(), "Error - population was not the one prepared");
+
+    let scored: Vec<_> = population
+      .iter()
+      .zip(&self.scores)
+      .map(|(individual, &score)| Scored::Borrowed(individual, score))
+      .collect();
+
+    self.selection_method
+      .select_parents(rng, &scored)
+      .map(Scored::individual)
+  }
+
+  // by fitness, there are no novelty scores for other populations
+  fn select_parents_unprepared<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    self.selection_method.select_parents_unprepared(rng, population)
+  }
+
+  // scores the population's novelty against the archive, then archives the
+  // behaviours novel enough
+  fn prepare<I>(&mut self, population: &[I])
+    where I: Individual {
+    self.evaluate(population);
+    self.selection_method.prepare(population);
+  }
+
+  // the archive's behaviours, and the scores of the population they were last
+  // prepared from
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    save(&(&self.archive.behaviours, &self.scores, self.selection_method.save_state()))
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    let (behaviours, scores, state) = load(state)?;
+    self.archive.behaviours = behaviours;
+    self.scores = scores;
+    self.selection_method.load_state(state)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  #[test]
+  fn novelty_is_mean_distance_to_nearest_neighbours() {
+    let archive = NoveltyArchive::new(2, 0.0, 10);
+    let behaviours = vec![vec![0.0], vec![1.0], vec![3.0]];
+
+    assert_eq!(archive.novelty(&behaviours), vec![2.0, 1.5, 2.5]);
+  }
+
+  #[test]
+  fn archives_novel_behaviours_up_to_capacity() {
+    let mut archive = NoveltyArchive::new(1, 1.5, 2);
+
+    let behaviours = vec![vec![0.0], vec![1.0], vec![5.0]];
+    let novelty = archive.novelty(&behaviours);
+    archive.extend(&behaviours, &novelty);
+    assert_eq!(archive.behaviours().collect::<Vec<_>>(), vec![&[5.0][..]]);
+
+    let behaviours = vec![vec![10.0], vec![20.0]];
+    let novelty = archive.novelty(&behaviours);
+    archive.extend(&behaviours, &novelty);
+    assert_eq!(archive.behaviours().collect::<Vec<_>>(), vec![&[10.0][..], &[20.0][..]]);
+  }
+
+  #[test]
+  fn prepare_scores_novelty() {
+    let population = [
+      TestIndividual::new(&[0.0], 9.0).with_behaviour(&[0.0]),
+      TestIndividual::new(&[0.0], 9.0).with_behaviour(&[0.1]),
+      TestIndividual::new(&[0.0], 1.0).with_behaviour(&[5.0]),
+    ];
+    let mut selection = NoveltySelection::new(
+      RouletteWheelSelection::new(),
+      NoveltyObjective::Novelty,
+      NoveltyArchive::new(1, 100.0, 10),
+    );
+
+    selection.prepare(&population);
+
+    let most_novel = selection.scores()
+      .iter()
+      .enumerate()
+      .max_by(|(_, a), (_, b)| a.total_cmp(b))
+      .map(|(index, _)| index);
+    assert_eq!(most_novel, Some(2));
+  }
+
+  #[test]
+  fn identical_behaviours_are_selected_uniformly() {
+    let mut rng = testing::rng();
+    let population: Vec<_> = (0..4)
+      .map(|i| TestIndividual::new(&[i as f64], 1.0).with_behaviour(&[0.5, 0.5]))
+      .collect();
+    let mut selection = NoveltySelection::new(
+      RouletteWheelSelection::new(),
+      NoveltyObjective::Novelty,
+      NoveltyArchive::new(2, 0.0, 10),
+    );
+
+    selection.prepare(&population);
+    assert_eq!(selection.scores(), [1.0; 4]);
+
+    let mut selected = [false; 4];
+    for _ in 0..100 {
+      for parent in selection.select_parents(&mut rng, &population) {
+        selected[parent.chromosome[0] as usize] = true;
+      }
+    }
+    assert_eq!(selected, [true; 4]);
+  }
+
+  #[test]
+  fn fitness_objective_needs_no_behaviours() {
+    let mut rng = testing::rng();
+    let population = [
+      TestIndividual::new(&[0.0], 0.0),
+      TestIndividual::new(&[1.0], 1.0),
+    ];
+    let mut selection = NoveltySelection::new(
+      RouletteWheelSelection::new(),
+      NoveltyObjective::Fitness,
+      NoveltyArchive::new(1, 0.0, 10),
+    );
+
+    selection.prepare(&population);
+    assert!(selection.scores().is_empty());
+    assert!(selection.archive().is_empty());
+
+    for _ in 0..10 {
+      assert_eq!(selection.select(&mut rng, &population).fitness, 1.0);
+    }
+  }
+
+  #[test]
+  fn selects_by_fitness_until_prepared() {
+    let mut rng = testing::rng();
+    let population = [
+      TestIndividual::new(&[0.0], 0.0).with_behaviour(&[0.0]),
+      TestIndividual::new(&[1.0], 1.0).with_behaviour(&[0.0]),
+    ];
+    let selection = NoveltySelection::new(
+      RouletteWheelSelection::new(),
+      NoveltyObjective::Novelty,
+      NoveltyArchive::new(1, 0.0, 10),
+    );
+
+    for _ in 0..10 {
+      assert_eq!(selection.select(&mut rng, &population).fitness, 1.0);
+    }
+  }
+}
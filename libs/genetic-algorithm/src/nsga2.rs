@@ -0,0 +1,401 @@
+use crate::*;
+
+// objectives of individuals that have several, and their fitness as the only
+// one otherwise
+pub(crate) fn objectives_of<I>(individual: &I) -> Vec<f64>
+  where I: Individual {
+  individual
+    .objectives()
+    .unwrap_or_else(|| vec![individual.fitness()])
+}
+
+// a dominates b when it is no worse on every objective and better on one
+pub fn dominates(a: &[f64], b: &[f64]) -> bool {
+  assert_eq!(a.len(), b.len());
+
+  let no_worse = a.iter().zip(b).all(|(a, b)| a >= b);
+  let better = a.iter().zip(b).any(|(a, b)| a > b);
+
+  no_worse && better
+}
+
+// deb's fast non-dominated sort, returns indices grouped into fronts, best first
+pub fn non_dominated_sort(objectives: &[Vec<f64>]) -> Vec<Vec<usize>> {
+  let n = objectives.len();
+
+  let mut dominated_by = vec![0; n]; // how many individuals dominate i
+  let mut dominating: Vec<Vec<usize>> = vec![Vec::new(); n]; // who i dominates
+  let mut fronts = vec![Vec::new()];
+
+  for i in 0..n {
+    for j in 0..n {
+      if dominates(&objectives[i], &objectives[j]) {
+        dominating[i].push(j);
+      } else if dominates(&objectives[j], &objectives[i]) {
+        dominated_by[i] += 1;
+      }
+    }
+
+    if dominated_by[i] == 0 {
+      fronts[0].push(i);
+    }
+  }
+
+  let mut current = 0;
+  while !fronts[current].is_empty() {
+    let mut next = Vec::new();
+
+    for &i in &fronts[current] {
+      for &j in &dominating[i] {
+        dominated_by[j] -= 1;
+        if dominated_by[j] == 0 {
+          next.push(j);
+        }
+      }
+    }
+
+    fronts.push(next);
+    current += 1;
+  }
+
+  fronts.pop(); // always empty
+  fronts
+}
+
+// crowding distance of every member of a front, in the same order as the front
+pub fn crowding_distance(objectives: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+  let mut distances = vec![0.0; front.len()];
+  if front.is_empty() {
+    return distances;
+  }
+
+  let objective_count = objectives[front[0]].len();
+  let columns = (0..objective_count)
+    .map(|m| front.iter().map(|&i| objectives[i][m]).collect::<Vec<_>>());
+
+  let mut order: Vec<_> = (0..front.len()).collect();
+
+  for values in columns {
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let first = order[0];
+    let last = order[front.len() - 1];
+    let range = values[last] - values[first];
+
+    distances[first] = f64::INFINITY;
+    distances[last] = f64::INFINITY;
+
+    if range <= 0.0 {
+      continue;
+    }
+
+    for w in order.windows(3) {
+      distances[w[1]] += (values[w[2]] - values[w[0]]) / range;
+    }
+  }
+
+  distances
+}
+
+// indices of the `count` survivors of a (mu + lambda) generation: whole fronts
+// while they fit, then the least crowded members of the first that does not
+pub fn environmental_selection(objectives: &[Vec<f64>], count: usize) -> Vec<usize> {
+  let mut survivors = Vec::with_capacity(count);
+
+  for front in non_dominated_sort(objectives) {
+    let room = count - survivors.len();
+    if room == 0 {
+      break;
+    }
+
+    if front.len() <= room {
+      survivors.extend(front);
+      continue;
+    }
+
+    let distances = crowding_distance(objectives, &front);
+    let mut order: Vec<_> = (0..front.len()).collect();
+    order.sort_by(|&a, &b| distances[b].total_cmp(&distances[a]));
+
+    survivors.extend(order[..room].iter().map(|&i| front[i]));
+  }
+
+  survivors
+}
+
+pub fn pareto_front<I>(population: &[I]) -> Vec<&I>
+  where I: Individual {
+  let objectives: Vec<_> = population
+    .iter()
+    .map(objectives_of)
+    .collect();
+
+  non_dominated_sort(&objectives)
+    .first()
+    .map(|front| front.iter().map(|&i| &population[i]).collect())
+    .unwrap_or_default()
+}
+
+// binary tournament under the crowded comparison operator: lower front wins,
+// ties are broken by larger crowding distance; ranks every population as it
+// is prepared
+#[derive(Clone, Debug, Default)]
+pub struct Nsga2Selection {
+  fronts: Vec<Vec<usize>>,
+  ranks: Vec<usize>,
+  crowding: Vec<f64>,
+}
+
+impl Nsga2Selection {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn rank(&mut self, objectives: &[Vec<f64>]) {
+    self.fronts = non_dominated_sort(objectives);
+    self.ranks = vec![0; objectives.len()];
+    self.crowding = vec![0.0; objectives.len()];
+
+    for (rank, front) in self.fronts.iter().enumerate() {
+      let distances = crowding_distance(objectives, front);
+
+      for (&i, distance) in front.iter().zip(distances) {
+        self.ranks[i] = rank;
+        self.crowding[i] = distance;
+      }
+    }
+  }
+
+  // indices of the non-dominated individuals of the last prepared population
+  pub fn pareto_front(&self) -> &[usize] {
+    self.fronts.first().map(Vec::as_slice).unwrap_or_default()
+  }
+
+  pub fn fronts(&self) -> &[Vec<usize>] {
+    &self.fronts
+  }
+
+  fn tournament(&self, rng: &mut impl RngCore, len: usize) -> usize {
+    assert!(len > 0);
+    assert_eq!(self.ranks.len(), len, "Error - population was not prepared");
+
+    let a = gen_index(rng, len);
+    let b = gen_index(rng, len);
+
+    if self.crowded_better(b, a) { b } else { a }
+  }
+
+  fn crowded_better(&self, a: usize, b: usize) -> bool {
+    self.ranks[a] < self.ranks[b]
+      || (self.ranks[a] == self.ranks[b] && self.crowding[a] > self.crowding[b])
+  }
+}
+
+impl SelectionMethod for Nsga2Selection {
+  fn select<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+    where I: Individual {
+    &population[self.tournament(rng, population.len())]
+  }
+
+  fn prepare<I>(&mut self, population: &[I])
+    where I: Individual {
+    let objectives: Vec<_> = population
+      .iter()
+      .map(objectives_of)
+      .collect();
+
+    self.rank(&objectives);
+  }
+}
+
+// chromosome that made it into the parents of an `Nsga2` generation
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Survivor {
+  chromosome: Chromosome,
+  objectives: Vec<f64>,
+}
+
+// elitist nsga-ii: every evaluated population competes with the parents it was
+// bred from, the best fronts of both survive as the next parents, and children
+// are bred from them by crowded tournaments; parents are remembered by their
+// chromosomes only, so children reach breed hooks as founders
+#[derive(Clone, Debug)]
+pub struct Nsga2<C, M> {
+  crossover_method: C,
+  mutation_method: M,
+  selection: Nsga2Selection,
+  survivors: Vec<Survivor>,
+}
+
+impl<C, M> Nsga2<C, M>
+  where C: CrossoverMethod,
+        M: MutationMethod, {
+
+  pub fn new(crossover_method: C, mutation_method: M) -> Self {
+    Self {
+      crossover_method,
+      mutation_method,
+      selection: Nsga2Selection::new(),
+      survivors: Vec::new(),
+    }
+  }
+
+  // parents of the last generation, with their objectives
+  pub fn survivors(&self) -> impl Iterator<Item = (&Chromosome, &[f64])> {
+    self.survivors
+      .iter()
+      .map(|survivor| (&survivor.chromosome, survivor.objectives.as_slice()))
+  }
+
+  // indices into `survivors` of the non-dominated ones
+  pub fn pareto_front(&self) -> &[usize] {
+    self.selection.pareto_front()
+  }
+
+  fn select<I>(&mut self, population: &[I])
+    where I: Individual<Gene = f64> {
+    let mut candidates: Vec<_> = population
+      .iter()
+      .map(|individual| Survivor {
+        chromosome: individual.as_ref().clone(),
+        objectives: objectives_of(individual),
+      })
+      .collect();
+    candidates.append(&mut self.survivors);
+
+    let objectives: Vec<_> = candidates
+      .iter()
+      .map(|candidate| candidate.objectives.clone())
+      .collect();
+    let mut survivors = environmental_selection(&objectives, population.len());
+    survivors.sort_unstable();
+
+    let mut candidates = candidates.into_iter().map(Some).collect::<Vec<_>>();
+    self.survivors = survivors
+      .into_iter()
+      .map(|i| candidates[i].take().expect("Error - survivor selected twice"))
+      .collect();
+
+    let objectives: Vec<_> = self.survivors
+      .iter()
+      .map(|survivor| survivor.objectives.clone())
+      .collect();
+    self.selection.rank(&objectives);
+  }
+}
+
+impl<C, M> Optimizer for Nsga2<C, M>
+  where C: CrossoverMethod,
+        M: MutationMethod, {
+
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = f64> {
+    assert!(!population.is_empty());
+
+    self.select(population);
+
+    let len = self.survivors.len();
+    let offspring = (0..population.len())
+      .map(|_| {
+        let parent_a = &self.survivors[self.selection.tournament(rng, len)].chromosome;
+        let parent_b = &self.survivors[self.selection.tournament(rng, len)].chromosome;
+
+        let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        self.mutation_method.mutate(rng, &mut child);
+
+        I::from(child)
+      })
+      .collect();
+
+    let stats = Statistics::new(population);
+
+    (offspring, stats)
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    self.mutation_method.adapt(history);
+  }
+
+  // the ranks are recomputed from the survivors' objectives
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    save(&self.survivors)
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    self.survivors = load(state)?;
+
+    let objectives: Vec<_> = self.survivors
+      .iter()
+      .map(|survivor| survivor.objectives.clone())
+      .collect();
+    self.selection.rank(&objectives);
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // four trade-offs, then one dominated front after another
+  fn objectives() -> Vec<Vec<f64>> {
+    [[1.0, 5.0], [2.0, 4.0], [3.0, 3.0], [4.0, 1.0], [1.0, 1.0], [2.0, 2.0], [0.0, 0.0]]
+      .map(Vec::from)
+      .to_vec()
+  }
+
+  #[test]
+  fn dominance() {
+    assert!(dominates(&[2.0, 2.0], &[1.0, 2.0]));
+    assert!(!dominates(&[2.0, 2.0], &[2.0, 2.0]));
+    assert!(!dominates(&[3.0, 1.0], &[1.0, 3.0]));
+  }
+
+  #[test]
+  fn sorts_into_fronts() {
+    assert_eq!(non_dominated_sort(&objectives()), vec![vec![0, 1, 2, 3], vec![5], vec![4], vec![6]]);
+  }
+
+  #[test]
+  fn crowding_distances() {
+    let distances = crowding_distance(&objectives(), &[0, 1, 2, 3]);
+
+    assert_eq!(distances[0], f64::INFINITY);
+    assert!((distances[1] - 7.0 / 6.0).abs() < 1e-12);
+    assert!((distances[2] - 17.0 / 12.0).abs() < 1e-12);
+    assert_eq!(distances[3], f64::INFINITY);
+  }
+
+  #[test]
+  fn environmental_selection_truncates_by_crowding() {
+    let mut survivors = environmental_selection(&objectives(), 3);
+    survivors.sort_unstable();
+    assert_eq!(survivors, vec![0, 2, 3]);
+
+    let mut survivors = environmental_selection(&objectives(), 5);
+    survivors.sort_unstable();
+    assert_eq!(survivors, vec![0, 1, 2, 3, 5]);
+  }
+
+  #[test]
+  fn elites_survive_worse_offspring() {
+    let mut rng = crate::testing::rng();
+    let mut nsga2 = Nsga2::new(UniformCrossover::new(), GaussianMutation::new(0.5, 0.5));
+
+    let elites: Vec<_> = (0..4)
+      .map(|i| crate::testing::TestIndividual::new(&[i as f64], 10.0))
+      .collect();
+    nsga2.next_generation(&mut rng, &elites);
+
+    let worse: Vec<_> = (0..4)
+      .map(|i| crate::testing::TestIndividual::new(&[i as f64], 1.0))
+      .collect();
+    nsga2.next_generation(&mut rng, &worse);
+
+    assert!(nsga2.survivors().all(|(_, objectives)| objectives == [10.0]));
+  }
+}
@@ -0,0 +1,158 @@
+use std::ops::ControlFlow;
+
+use crate::*;
+
+// hooks into every generation, to log, plot or stop evolution from outside;
+// whoever counts generations reports their start and end, the optimizer
+// reports the parents it selects and the offspring it breeds
+pub trait Observer<G = f64> {
+  fn generation_start(&mut self, _generation: usize) {}
+
+  fn after_selection(&mut self, _parent_a: &Chromosome<G>, _parent_b: &Chromosome<G>) {}
+
+  fn after_offspring(&mut self, _child: &Chromosome<G>) {}
+
+  // break to stop evolving once this generation is done
+  fn generation_end(&mut self, _generation: usize, _stats: &Statistics) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+  }
+}
+
+// observes nothing
+impl<G> Observer<G> for () {}
+
+impl<G, O> Observer<G> for &mut O
+  where O: Observer<G> + ?Sized, {
+
+  fn generation_start(&mut self, generation: usize) {
+    (**self).generation_start(generation)
+  }
+
+  fn after_selection(&mut self, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) {
+    (**self).after_selection(parent_a, parent_b)
+  }
+
+  fn after_offspring(&mut self, child: &Chromosome<G>) {
+    (**self).after_offspring(child)
+  }
+
+  fn generation_end(&mut self, generation: usize, stats: &Statistics) -> ControlFlow<()> {
+    (**self).generation_end(generation, stats)
+  }
+}
+
+impl<G, O> Observer<G> for Box<O>
+  where O: Observer<G> + ?Sized, {
+
+  fn generation_start(&mut self, generation: usize) {
+    (**self).generation_start(generation)
+  }
+
+  fn after_selection(&mut self, parent_a: &Chromosome<G>, parent_b: &Chromosome<G>) {
+    (**self).after_selection(parent_a, parent_b)
+  }
+
+  fn after_offspring(&mut self, child: &Chromosome<G>) {
+    (**self).after_offspring(child)
+  }
+
+  fn generation_end(&mut self, generation: usize, stats: &Statistics) -> ControlFlow<()> {
+    (**self).generation_end(generation, stats)
+  }
+}
+
+// observer out of a closure, for when only finished generations matter
+pub struct OnGenerationEnd<F>(pub F);
+
+impl<G, F> Observer<G> for OnGenerationEnd<F>
+  where F: FnMut(usize, &Statistics) -> ControlFlow<()>, {
+
+  fn generation_end(&mut self, generation: usize, stats: &Statistics) -> ControlFlow<()> {
+    (self.0)(generation, stats)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{rng, TestIndividual};
+
+  #[derive(Default)]
+  struct Recorder {
+    starts: Vec<usize>,
+    selections: usize,
+    offspring: usize,
+    ends: Vec<usize>,
+  }
+
+  impl Observer for Recorder {
+    fn generation_start(&mut self, generation: usize) {
+      self.starts.push(generation);
+    }
+
+    fn after_selection(&mut self, _parent_a: &Chromosome, _parent_b: &Chromosome) {
+      self.selections += 1;
+    }
+
+    fn after_offspring(&mut self, _child: &Chromosome) {
+      self.offspring += 1;
+    }
+
+    fn generation_end(&mut self, generation: usize, _stats: &Statistics) -> ControlFlow<()> {
+      self.ends.push(generation);
+      ControlFlow::Continue(())
+    }
+  }
+
+  fn ga() -> GeneticAlgorithm<RouletteWheelSelection, UniformCrossover, GaussianMutation> {
+    GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover::new(), GaussianMutation::new(0.5, 0.5))
+  }
+
+  fn population() -> Vec<TestIndividual> {
+    (0..8)
+      .map(|i| TestIndividual::new(&[i as f64, 1.0], i as f64 + 1.0))
+      .collect()
+  }
+
+  #[test]
+  fn sees_every_selection_and_offspring() {
+    let mut recorder = Recorder::default();
+    let (children, _) = ga().evolve_observed(&mut rng(), &population(), &mut recorder);
+
+    assert_eq!(recorder.selections, children.len());
+    assert_eq!(recorder.offspring, children.len());
+  }
+
+  #[test]
+  fn forwards_through_a_sendable_box() {
+    let mut observer: Box<dyn Observer + Send> = Box::new(OnGenerationEnd(|generation, _: &Statistics| {
+      if generation == 2 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    }));
+    let stats = Statistics::new(&population());
+
+    assert!(observer.generation_end(1, &stats).is_continue());
+    assert!(observer.generation_end(2, &stats).is_break());
+  }
+
+  #[test]
+  fn numbers_generations_of_a_run() {
+    let mut recorder = Recorder::default();
+    let mut driver = Driver::new(ga(), MaxGenerations(3));
+    driver.run_observed(&mut rng(), population(), |_, population| population, &mut recorder);
+
+    assert_eq!(recorder.starts, [0, 1, 2]);
+    assert_eq!(recorder.ends, [0, 1, 2]);
+  }
+
+  #[test]
+  fn breaking_stops_a_run() {
+    let mut observer = OnGenerationEnd(|generation, _: &Statistics| {
+      if generation == 1 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    });
+    let mut driver = Driver::new(ga(), MaxGenerations(10));
+    let (summary, _) = driver.run_observed(&mut rng(), population(), |_, population| population, &mut observer);
+
+    assert_eq!(summary.reason, StopReason::Observer);
+    assert_eq!(summary.generations, 2);
+  }
+}
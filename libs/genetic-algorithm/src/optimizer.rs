@@ -0,0 +1,178 @@
+#[cfg(feature = "serde")]
+use std::io;
+
+use crate::*;
+
+// anything that turns an evaluated population into the next one to evaluate
+pub trait Optimizer<G = f64> {
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G>;
+
+  // like `next_generation`, reporting to `observer` and handing every child to
+  // `hook`; optimizers that do not breed from parents only report their
+  // offspring, and hand them over as founders
+  fn next_generation_with<I>(
+    &mut self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn Observer<G>,
+    hook: &mut dyn BreedHook<I>,
+  ) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G> {
+    let (mut new_population, stats) = self.next_generation(rng, population);
+
+    for child in &mut new_population {
+      observer.after_offspring(child.as_ref());
+      hook.founded(rng, child);
+    }
+
+    (new_population, stats)
+  }
+
+  fn next_generation_with_lineage<I>(&mut self, rng: &mut impl RngCore, population: &[I], lineage: &mut Lineage) -> (Vec<I>, Statistics)
+    where I: TrackedIndividual<Gene = G> {
+    self.next_generation_with_lineage_observed(rng, population, lineage, &mut ())
+  }
+
+  fn next_generation_observed<I>(&mut self, rng: &mut impl RngCore, population: &[I], observer: &mut dyn Observer<G>) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G> {
+    self.next_generation_with(rng, population, observer, &mut ())
+  }
+
+  fn next_generation_with_lineage_observed<I>(
+    &mut self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    lineage: &mut Lineage,
+    observer: &mut dyn Observer<G>,
+  ) -> (Vec<I>, Statistics)
+    where I: TrackedIndividual<Gene = G> {
+    lineage.advance();
+    self.next_generation_with(rng, population, observer, lineage)
+  }
+
+  // sees the statistics of every generation so far once each is evolved, for
+  // optimizers whose operators change as the run goes; `Driver` calls it
+  fn adapt(&mut self, _history: &[Statistics]) {}
+
+  // whatever the optimizer carries from one generation to the next, for
+  // checkpoints; null for stateless ones
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    serde_json::Value::Null
+  }
+
+  // restores what `save_state` returned, on an optimizer configured the same
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, _state: serde_json::Value) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+// breeds in parallel with the parallel feature, see `evolve_parallel`
+impl<S, C, M, G> Optimizer<G> for GeneticAlgorithm<S, C, M>
+  where S: SelectionMethod + MaybeSync,
+        C: CrossoverMethod<G> + MaybeSync,
+        M: MutationMethod<G> + MaybeSync,
+        G: Gene, {
+
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G> {
+    self.next_generation_with(rng, population, &mut (), &mut ())
+  }
+
+  fn next_generation_with<I>(
+    &mut self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn Observer<G>,
+    hook: &mut dyn BreedHook<I>,
+  ) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G> {
+    self.prepare(population);
+
+    #[cfg(feature = "parallel")]
+    return self.evolve_parallel_with(rng, population, observer, hook);
+
+    #[cfg(not(feature = "parallel"))]
+    self.evolve_with(rng, population, observer, hook)
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    self.mutation_method.adapt(history);
+  }
+
+  // selection methods like species and novelty archives keep state, the
+  // operators do not
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    self.selection_method.save_state()
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> io::Result<()> {
+    self.selection_method.load_state(state)
+  }
+}
+
+impl<O, G> Optimizer<G> for &mut O
+  where O: Optimizer<G>, {
+
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G> {
+    (**self).next_generation(rng, population)
+  }
+
+  fn next_generation_with<I>(
+    &mut self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn Observer<G>,
+    hook: &mut dyn BreedHook<I>,
+  ) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = G> {
+    (**self).next_generation_with(rng, population, observer, hook)
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    (**self).adapt(history)
+  }
+
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    (**self).save_state()
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> io::Result<()> {
+    (**self).load_state(state)
+  }
+}
+
+// chromosome remembered by an optimizer together with the fitness it scored
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Evaluated {
+  pub(crate) chromosome: Chromosome,
+  pub(crate) fitness: f64,
+}
+
+impl Evaluated {
+  pub(crate) fn new<I>(individual: &I) -> Self
+    where I: Individual<Gene = f64> {
+    Self {
+      chromosome: individual.as_ref().clone(),
+      fitness: individual.fitness(),
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn save<T: serde::Serialize>(state: &T) -> serde_json::Value {
+  serde_json::to_value(state).expect("Error - optimizer state is plain data")
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn load<T: serde::de::DeserializeOwned>(state: serde_json::Value) -> io::Result<T> {
+  serde_json::from_value(state).map_err(io::Error::from)
+}
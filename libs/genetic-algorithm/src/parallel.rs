@@ -0,0 +1,103 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+
+use crate::*;
+
+impl<S, C, M> GeneticAlgorithm<S, C, M>
+  where S: SelectionMethod + Sync,
+        C: Sync,
+        M: Sync, {
+
+  // children are bred across threads, each with its own rng seeded from a
+  // single draw of `rng` and streamed by the child's index, so the new
+  // population does not depend on the number of threads; every thread breeds
+  // a chunk of children, so selection methods prepare to select once per
+  // chunk; the optimizer breeds this way whenever the parallel feature is on
+  pub fn evolve_parallel<I>(&self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual + Send + Sync,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    self.evolve_parallel_with(rng, population, &mut (), &mut ())
+  }
+
+  // like `evolve_parallel`, then reporting every child to `observer` and
+  // handing it to `hook` on the calling thread, in order
+  pub fn evolve_parallel_with<I>(
+    &self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn Observer<I::Gene>,
+    hook: &mut dyn BreedHook<I>,
+  ) -> (Vec<I>, Statistics)
+    where I: Individual + Send + Sync,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    assert!(!population.is_empty());
+
+    let seed = rng.next_u64();
+    let scaled = self.scaled(population);
+
+    let chunk = population.len().div_ceil(rayon::current_num_threads());
+
+    let births: Vec<_> = (0..population.len())
+      .into_par_iter()
+      .chunks(chunk)
+      .flat_map_iter(|indices| {
+        self.selection_method.with_parents(&scaled, |select| {
+          indices
+            .into_iter()
+            .map(|index| {
+              let mut rng = ChaCha8Rng::seed_from_u64(seed);
+              rng.set_stream(index as u64);
+
+              self.conceive(&mut rng, select)
+            })
+            .collect::<Vec<_>>()
+        })
+      })
+      .collect();
+
+    let new_population = births
+      .into_iter()
+      .map(|birth| Self::deliver(rng, birth, observer, hook))
+      .collect();
+
+    let stats = Statistics::new(population);
+
+    (new_population, stats)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::TestIndividual;
+
+  fn evolve_on(threads: usize, population: &[TestIndividual]) -> Vec<Vec<f64>> {
+    let ga = GeneticAlgorithm::new(
+      RouletteWheelSelection::new(),
+      UniformCrossover::new(),
+      GaussianMutation::new(0.5, 0.5),
+    );
+    let pool = rayon::ThreadPoolBuilder::new()
+      .num_threads(threads)
+      .build()
+      .unwrap();
+
+    let (children, _) = pool.install(|| ga.evolve_parallel(&mut crate::testing::rng(), population));
+    children
+      .iter()
+      .map(|child| child.chromosome.iter().copied().collect())
+      .collect()
+  }
+
+  #[test]
+  fn does_not_depend_on_thread_count() {
+    let population: Vec<_> = (0..32)
+      .map(|i| TestIndividual::new(&[i as f64, -i as f64, 0.5], i as f64 + 1.0))
+      .collect();
+
+    assert_eq!(evolve_on(1, &population), evolve_on(4, &population));
+  }
+}
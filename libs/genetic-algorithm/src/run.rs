@@ -0,0 +1,345 @@
+use std::time::{Duration, Instant};
+
+use crate::*;
+use crate::schedule::stagnant_generations;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+  MaxGenerations,
+  TargetFitness,
+  TimeBudget,
+  Stagnation,
+  DiversityCollapse,
+  Observer, // an observer broke out at the end of a generation
+}
+
+// decides after every generation whether a run is over, given every
+// generation's statistics so far and the time spent
+pub trait Termination {
+  fn check(&self, history: &[Statistics], elapsed: Duration) -> Option<StopReason>;
+
+  fn or<T>(self, other: T) -> Or<Self, T>
+    where Self: Sized,
+          T: Termination, {
+    Or(self, other)
+  }
+
+  fn and<T>(self, other: T) -> And<Self, T>
+    where Self: Sized,
+          T: Termination, {
+    And(self, other)
+  }
+}
+
+// stops as soon as either condition does
+#[derive(Clone, Debug)]
+pub struct Or<A, B>(A, B);
+
+impl<A, B> Termination for Or<A, B>
+  where A: Termination,
+        B: Termination, {
+
+  fn check(&self, history: &[Statistics], elapsed: Duration) -> Option<StopReason> {
+    self.0
+      .check(history, elapsed)
+      .or_else(|| self.1.check(history, elapsed))
+  }
+}
+
+// stops only once both conditions do, reporting the first one's reason
+#[derive(Clone, Debug)]
+pub struct And<A, B>(A, B);
+
+impl<A, B> Termination for And<A, B>
+  where A: Termination,
+        B: Termination, {
+
+  fn check(&self, history: &[Statistics], elapsed: Duration) -> Option<StopReason> {
+    let reason = self.0.check(history, elapsed)?;
+    self.1.check(history, elapsed).map(|_| reason)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct MaxGenerations(pub usize);
+
+impl Termination for MaxGenerations {
+  fn check(&self, history: &[Statistics], _elapsed: Duration) -> Option<StopReason> {
+    (history.len() >= self.0).then_some(StopReason::MaxGenerations)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct TargetFitness(pub f64);
+
+impl Termination for TargetFitness {
+  fn check(&self, history: &[Statistics], _elapsed: Duration) -> Option<StopReason> {
+    history
+      .last()
+      .filter(|stats| stats.max_fitness() >= self.0)
+      .map(|_| StopReason::TargetFitness)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct TimeBudget(pub Duration);
+
+impl Termination for TimeBudget {
+  fn check(&self, _history: &[Statistics], elapsed: Duration) -> Option<StopReason> {
+    (elapsed >= self.0).then_some(StopReason::TimeBudget)
+  }
+}
+
+// max fitness has not improved by more than epsilon for this many generations
+#[derive(Clone, Debug)]
+pub struct Stagnation {
+  pub generations: usize,
+  pub epsilon: f64,
+}
+
+impl Termination for Stagnation {
+  fn check(&self, history: &[Statistics], _elapsed: Duration) -> Option<StopReason> {
+    (stagnant_generations(history, self.epsilon) >= self.generations).then_some(StopReason::Stagnation)
+  }
+}
+
+// mean pairwise chromosome distance fell below a threshold
+#[derive(Clone, Debug)]
+pub struct DiversityCollapse(pub f64);
+
+impl Termination for DiversityCollapse {
+  fn check(&self, history: &[Statistics], _elapsed: Duration) -> Option<StopReason> {
+    history
+      .last()
+      .filter(|stats| stats.diversity() < self.0)
+      .map(|_| StopReason::DiversityCollapse)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct RunSummary<G = f64> {
+  pub reason: StopReason,
+  pub generations: usize,
+  pub elapsed: Duration,
+  pub best_fitness: f64,
+  pub best_chromosome: Chromosome<G>,
+  pub history: Vec<Statistics>,
+}
+
+// evaluates and evolves a population with an optimizer until a termination
+// condition holds; relies on the system clock, so it is native only
+pub struct Driver<O, T> {
+  optimizer: O,
+  termination: T,
+}
+
+impl<O, T> Driver<O, T>
+  where T: Termination, {
+
+  pub fn new(optimizer: O, termination: T) -> Self {
+    Self { optimizer, termination }
+  }
+
+  pub fn optimizer(&self) -> &O {
+    &self.optimizer
+  }
+
+  pub fn into_optimizer(self) -> O {
+    self.optimizer
+  }
+
+  // `evaluate` scores a freshly bred population and hands it back evaluated;
+  // returns the summary along with the next, not yet evaluated, population
+  pub fn run<R, I, F>(&mut self, rng: &mut R, population: Vec<I>, evaluate: F) -> (RunSummary<I::Gene>, Vec<I>)
+    where O: Optimizer<I::Gene>,
+          R: RngCore,
+          I: Individual,
+          F: FnMut(&mut R, Vec<I>) -> Vec<I>, {
+    self.run_observed(rng, population, evaluate, &mut ())
+  }
+
+  // like `run`, reporting every generation to `observer`, which may also stop it
+  pub fn run_observed<R, I, F>(&mut self, rng: &mut R, population: Vec<I>, evaluate: F, observer: &mut dyn Observer<I::Gene>) -> (RunSummary<I::Gene>, Vec<I>)
+    where O: Optimizer<I::Gene>,
+          R: RngCore,
+          I: Individual,
+          F: FnMut(&mut R, Vec<I>) -> Vec<I>, {
+    self.run_with(
+      rng,
+      population,
+      evaluate,
+      |optimizer, rng, evaluated, observer| optimizer.next_generation_observed(rng, evaluated, observer),
+      observer,
+    )
+  }
+
+  // like `run`, also recording every generation's offspring in `lineage`
+  pub fn run_with_lineage<R, I, F>(&mut self, rng: &mut R, population: Vec<I>, lineage: &mut Lineage, evaluate: F) -> (RunSummary<I::Gene>, Vec<I>)
+    where O: Optimizer<I::Gene>,
+          R: RngCore,
+          I: TrackedIndividual,
+          F: FnMut(&mut R, Vec<I>) -> Vec<I>, {
+    self.run_with(
+      rng,
+      population,
+      evaluate,
+      |optimizer, rng, evaluated, observer| optimizer.next_generation_with_lineage_observed(rng, evaluated, lineage, observer),
+      &mut (),
+    )
+  }
+
+  // like `run_observed`, breeding every generation with `next_generation`
+  // instead of handing the population straight to the optimizer
+  pub fn run_with<R, I, F, N>(
+    &mut self,
+    rng: &mut R,
+    population: Vec<I>,
+    mut evaluate: F,
+    mut next_generation: N,
+    observer: &mut dyn Observer<I::Gene>,
+  ) -> (RunSummary<I::Gene>, Vec<I>)
+    where O: Optimizer<I::Gene>,
+          R: RngCore,
+          I: Individual,
+          F: FnMut(&mut R, Vec<I>) -> Vec<I>,
+          N: FnMut(&mut O, &mut R, &[I], &mut dyn Observer<I::Gene>) -> (Vec<I>, Statistics), {
+    assert!(!population.is_empty());
+
+    let start = Instant::now();
+    let mut history = Vec::new();
+    let mut best: Option<(f64, Chromosome<I::Gene>)> = None;
+    let mut population = population;
+
+    loop {
+      let generation = history.len();
+      observer.generation_start(generation);
+
+      let evaluated = evaluate(rng, population);
+      let (next_population, stats) = next_generation(&mut self.optimizer, rng, &evaluated, observer);
+
+      let champion = &evaluated[stats.best_index()];
+      if best.as_ref().is_none_or(|(fitness, _)| champion.fitness() > *fitness) {
+        best = Some((champion.fitness(), champion.as_ref().clone()));
+      }
+
+      let stopped = observer.generation_end(generation, &stats).is_break();
+      history.push(stats);
+      self.optimizer.adapt(&history);
+
+      let reason = if stopped {
+        Some(StopReason::Observer)
+      } else {
+        self.termination.check(&history, start.elapsed())
+      };
+
+      if let Some(reason) = reason {
+        let (best_fitness, best_chromosome) = best.expect("Error - no generation was run");
+
+        let summary = RunSummary {
+          reason,
+          generations: history.len(),
+          elapsed: start.elapsed(),
+          best_fitness,
+          best_chromosome,
+          history,
+        };
+
+        return (summary, next_population);
+      }
+
+      population = next_population;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{rng, TestIndividual};
+
+  // one generation per max fitness, with every chromosome identical
+  // (diversity 0) or spread apart (diversity 1) as asked
+  fn history(max_fitnesses: &[f64], diverse: bool) -> Vec<Statistics> {
+    max_fitnesses
+      .iter()
+      .map(|&max| {
+        let spread = if diverse { 1.0 } else { 0.0 };
+        Statistics::new(&[TestIndividual::new(&[0.0], 0.0), TestIndividual::new(&[spread], max)])
+      })
+      .collect()
+  }
+
+  const NOW: Duration = Duration::ZERO;
+
+  #[test]
+  fn max_generations() {
+    assert_eq!(MaxGenerations(3).check(&history(&[1.0, 2.0], true), NOW), None);
+    assert_eq!(MaxGenerations(3).check(&history(&[1.0, 2.0, 3.0], true), NOW), Some(StopReason::MaxGenerations));
+  }
+
+  #[test]
+  fn target_fitness_looks_at_the_last_generation() {
+    assert_eq!(TargetFitness(5.0).check(&history(&[], true), NOW), None);
+    assert_eq!(TargetFitness(5.0).check(&history(&[6.0, 4.0], true), NOW), None);
+    assert_eq!(TargetFitness(5.0).check(&history(&[4.0, 5.0], true), NOW), Some(StopReason::TargetFitness));
+  }
+
+  #[test]
+  fn time_budget() {
+    let budget = TimeBudget(Duration::from_secs(1));
+    assert_eq!(budget.check(&[], Duration::from_millis(999)), None);
+    assert_eq!(budget.check(&[], Duration::from_secs(1)), Some(StopReason::TimeBudget));
+  }
+
+  #[test]
+  fn stagnation_counts_generations_without_improvement() {
+    let stagnation = Stagnation { generations: 2, epsilon: 0.5 };
+
+    assert_eq!(stagnation.check(&history(&[1.0, 2.0, 3.0], true), NOW), None);
+    assert_eq!(stagnation.check(&history(&[1.0, 2.0, 2.2, 2.4], true), NOW), Some(StopReason::Stagnation));
+  }
+
+  #[test]
+  fn diversity_collapse() {
+    assert_eq!(DiversityCollapse(0.5).check(&history(&[1.0], true), NOW), None);
+    assert_eq!(DiversityCollapse(0.5).check(&history(&[1.0], false), NOW), Some(StopReason::DiversityCollapse));
+  }
+
+  #[test]
+  fn combinators() {
+    let history = history(&[1.0, 2.0], true);
+
+    let either = MaxGenerations(2).or(TargetFitness(10.0));
+    assert_eq!(either.check(&history, NOW), Some(StopReason::MaxGenerations));
+
+    let both = TargetFitness(1.5).and(MaxGenerations(3));
+    assert_eq!(both.check(&history, NOW), None);
+
+    let both = TargetFitness(1.5).and(MaxGenerations(2));
+    assert_eq!(both.check(&history, NOW), Some(StopReason::TargetFitness));
+  }
+
+  #[test]
+  fn driver_reports_the_best_individual_ever_seen() {
+    let ga = GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover::new(), GaussianMutation::new(0.5, 0.5));
+    let mut driver = Driver::new(ga, MaxGenerations(4));
+    let population: Vec<_> = (0..6).map(|i| TestIndividual::new(&[i as f64], 1.0)).collect();
+
+    // only the first generation ever scores 7, at the individual with gene 2
+    let mut generation = 0;
+    let (summary, next) = driver.run(&mut rng(), population, |_, mut population| {
+      for (index, individual) in population.iter_mut().enumerate() {
+        individual.fitness = if generation == 0 && index == 2 { 7.0 } else { 1.0 };
+      }
+      generation += 1;
+      population
+    });
+
+    assert_eq!(summary.reason, StopReason::MaxGenerations);
+    assert_eq!(summary.generations, 4);
+    assert_eq!(summary.history.len(), 4);
+    assert_eq!(summary.best_fitness, 7.0);
+    assert!(summary.best_chromosome.iter().eq([2.0].iter()));
+    assert_eq!(next.len(), 6);
+  }
+}
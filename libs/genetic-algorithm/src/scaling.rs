@@ -0,0 +1,104 @@
+// how raw fitness is transformed before selection sees it; whatever the
+// transformation, negative results are clamped to zero and a population
+// left without any positive fitness is selected from uniformly
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FitnessScaling {
+  #[default]
+  Identity,
+  Linear { multiple: f64 }, // average maps to 1 and the best to `multiple`
+  SigmaTruncation { c: f64 }, // f - (avg - c * std_dev)
+  Windowing { floor: f64 }, // f - worst + floor, over the current generation
+  PowerLaw { exponent: f64 }, // f^exponent
+  Boltzmann { temperature: f64 }, // e^(f / temperature), higher is gentler
+}
+
+impl FitnessScaling {
+  pub fn scale(&self, fitnesses: &[f64]) -> Vec<f64> {
+    if fitnesses.is_empty() {
+      return Vec::new();
+    }
+
+    let len = fitnesses.len() as f64;
+    let min = fitnesses.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = fitnesses.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let avg = fitnesses.iter().sum::<f64>() / len;
+    let std_dev = (fitnesses.iter().map(|f| (f - avg).powi(2)).sum::<f64>() / len).sqrt();
+
+    let scaled: Vec<_> = fitnesses
+      .iter()
+      .map(|&f| match *self {
+        Self::Identity => f,
+        Self::Linear { multiple } if max > avg => 1.0 + (multiple - 1.0) * (f - avg) / (max - avg),
+        Self::Linear { .. } => 1.0,
+        Self::SigmaTruncation { c } => f - (avg - c * std_dev),
+        Self::Windowing { floor } => f - min + floor,
+        Self::PowerLaw { exponent } => f.max(0.0).powf(exponent),
+        // shifted by the best, which leaves selection odds alone but cannot overflow
+        Self::Boltzmann { temperature } => ((f - max) / temperature).exp(),
+      })
+      .map(|f| f.max(0.0))
+      .collect();
+
+    let total = scaled.iter().sum::<f64>();
+    if total > 0.0 && total.is_finite() {
+      scaled
+    } else {
+      vec![1.0; scaled.len()]
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn close(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12)
+  }
+
+  #[test]
+  fn empty_population() {
+    assert!(FitnessScaling::Linear { multiple: 2.0 }.scale(&[]).is_empty());
+  }
+
+  #[test]
+  fn linear_maps_the_average_to_one_and_the_best_to_the_multiple() {
+    let scaled = FitnessScaling::Linear { multiple: 2.0 }.scale(&[1.0, 2.0, 3.0]);
+    assert!(close(&scaled, &[0.0, 1.0, 2.0]));
+  }
+
+  #[test]
+  fn linear_of_a_uniform_population() {
+    let scaled = FitnessScaling::Linear { multiple: 2.0 }.scale(&[5.0, 5.0]);
+    assert!(close(&scaled, &[1.0, 1.0]));
+  }
+
+  #[test]
+  fn negative_results_are_clamped() {
+    let scaled = FitnessScaling::SigmaTruncation { c: 0.0 }.scale(&[1.0, 2.0, 3.0]);
+    assert!(close(&scaled, &[0.0, 0.0, 1.0]));
+
+    let scaled = FitnessScaling::PowerLaw { exponent: 2.0 }.scale(&[-3.0, 2.0]);
+    assert!(close(&scaled, &[0.0, 4.0]));
+  }
+
+  #[test]
+  fn nothing_positive_selects_uniformly() {
+    assert!(close(&FitnessScaling::Identity.scale(&[-1.0, -2.0]), &[1.0, 1.0]));
+    assert!(close(&FitnessScaling::Windowing { floor: 0.0 }.scale(&[4.0, 4.0]), &[1.0, 1.0]));
+  }
+
+  #[test]
+  fn windowing_lifts_the_worst_to_the_floor() {
+    let scaled = FitnessScaling::Windowing { floor: 0.5 }.scale(&[-2.0, 0.0, 1.0]);
+    assert!(close(&scaled, &[0.5, 2.5, 3.5]));
+  }
+
+  #[test]
+  fn boltzmann_survives_huge_fitness() {
+    // e^1000 alone would overflow
+    let scaled = FitnessScaling::Boltzmann { temperature: 1.0 }.scale(&[1000.0, 1000.0 - 2.0_f64.ln()]);
+    assert!(close(&scaled, &[1.0, 0.5]));
+  }
+}
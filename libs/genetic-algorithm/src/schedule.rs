@@ -0,0 +1,320 @@
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MutationRates {
+  pub chance: f64, // chance of change
+  pub mag: f64, // magnitude of change
+}
+
+impl MutationRates {
+  pub fn new(chance: f64, mag: f64) -> Self {
+    Self { chance, mag }
+  }
+
+  fn scaled(self, chance_factor: f64, mag_factor: f64) -> Self {
+    Self {
+      chance: (self.chance * chance_factor).clamp(0.0, 1.0),
+      mag: self.mag * mag_factor,
+    }
+  }
+}
+
+// mutation methods whose chance and magnitude can be changed between generations
+pub trait AdaptiveMutation: MutationMethod {
+  fn rates(&self) -> MutationRates;
+  fn set_rates(&mut self, rates: MutationRates);
+}
+
+// computes the rates for the next generation from the base rates and every
+// generation's statistics so far, oldest first
+pub trait MutationSchedule {
+  fn rates(&self, base: MutationRates, history: &[Statistics]) -> MutationRates;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ConstantSchedule;
+
+impl ConstantSchedule {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+impl MutationSchedule for ConstantSchedule {
+  fn rates(&self, base: MutationRates, _history: &[Statistics]) -> MutationRates {
+    base
+  }
+}
+
+// exponentially decays both rates every generation, never going below a floor
+#[derive(Clone, Debug)]
+pub struct GenerationDecay {
+  decay: f64, // multiplier applied per generation
+  floor: MutationRates,
+}
+
+impl GenerationDecay {
+  pub fn new(decay: f64, floor: MutationRates) -> Self {
+    assert!((0.0..=1.0).contains(&decay));
+    Self { decay, floor }
+  }
+}
+
+impl MutationSchedule for GenerationDecay {
+  fn rates(&self, base: MutationRates, history: &[Statistics]) -> MutationRates {
+    let factor = self.decay.powi(history.len() as i32);
+    let rates = base.scaled(factor, factor);
+
+    MutationRates {
+      chance: rates.chance.max(self.floor.chance),
+      mag: rates.mag.max(self.floor.mag),
+    }
+  }
+}
+
+// boosts both rates while the max fitness has not improved for a while
+#[derive(Clone, Debug)]
+pub struct StagnationBoost {
+  generations: usize, // generations without improvement before boosting
+  boost: f64, // multiplier applied while stagnant
+  epsilon: f64, // smallest change counted as an improvement
+}
+
+impl StagnationBoost {
+  pub fn new(generations: usize, boost: f64, epsilon: f64) -> Self {
+    assert!(generations > 0);
+    assert!(boost >= 1.0);
+    Self { generations, boost, epsilon }
+  }
+}
+
+impl MutationSchedule for StagnationBoost {
+  fn rates(&self, base: MutationRates, history: &[Statistics]) -> MutationRates {
+    if stagnant_generations(history, self.epsilon) >= self.generations {
+      base.scaled(self.boost, self.boost)
+    } else {
+      base
+    }
+  }
+}
+
+// rechenberg's 1/5th success rule applied to the magnitude; a generation is a
+// success when it raised the max fitness, and after every window the magnitude
+// grows if more than a fifth of the window succeeded and shrinks otherwise
+#[derive(Clone, Debug)]
+pub struct OneFifthRule {
+  window: usize,
+  factor: f64, // shrink multiplier, its inverse is used to grow
+}
+
+impl OneFifthRule {
+  pub fn new(window: usize, factor: f64) -> Self {
+    assert!(window > 0);
+    assert!(factor > 0.0 && factor < 1.0);
+    Self { window, factor }
+  }
+}
+
+impl Default for OneFifthRule {
+  fn default() -> Self {
+    Self::new(10, 0.82)
+  }
+}
+
+impl MutationSchedule for OneFifthRule {
+  fn rates(&self, base: MutationRates, history: &[Statistics]) -> MutationRates {
+    let successes: Vec<_> = history
+      .windows(2)
+      .map(|pair| pair[1].max_fitness() > pair[0].max_fitness())
+      .collect();
+
+    let mag_factor = successes
+      .chunks_exact(self.window)
+      .fold(1.0, |mag_factor, window| {
+        let success_rate = window.iter().filter(|&&success| success).count() as f64 / self.window as f64;
+
+        if success_rate > 0.2 {
+          mag_factor / self.factor
+        } else if success_rate < 0.2 {
+          mag_factor * self.factor
+        } else {
+          mag_factor
+        }
+      });
+
+    base.scaled(1.0, mag_factor)
+  }
+}
+
+pub(crate) fn stagnant_generations(history: &[Statistics], epsilon: f64) -> usize {
+  let mut best = f64::NEG_INFINITY;
+  let mut stagnant = 0;
+
+  for stats in history {
+    if stats.max_fitness() > best + epsilon {
+      best = stats.max_fitness();
+      stagnant = 0;
+    } else {
+      stagnant += 1;
+    }
+  }
+
+  stagnant
+}
+
+// mutation whose rates are recomputed by a schedule after every generation
+#[derive(Clone, Debug)]
+pub struct ScheduledMutation<M, S> {
+  base: MutationRates,
+  method: M,
+  schedule: S,
+}
+
+impl<M, S> ScheduledMutation<M, S>
+  where M: AdaptiveMutation,
+        S: MutationSchedule, {
+
+  pub fn new(method: M, schedule: S) -> Self {
+    Self {
+      base: method.rates(),
+      method,
+      schedule,
+    }
+  }
+
+  pub fn rates(&self) -> MutationRates {
+    self.method.rates()
+  }
+}
+
+// rates are recomputed from the base rates whenever the optimizer adapts
+impl<M, S> MutationMethod for ScheduledMutation<M, S>
+  where M: AdaptiveMutation,
+        S: MutationSchedule, {
+
+  fn mutate(&self, rng: &mut impl RngCore, child: &mut Chromosome) {
+    self.method.mutate(rng, child);
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    let rates = self.schedule.rates(self.base, history);
+    self.method.set_rates(rates);
+  }
+}
+
+impl<S> MutationSchedule for Box<S>
+  where S: MutationSchedule + ?Sized, {
+
+  fn rates(&self, base: MutationRates, history: &[Statistics]) -> MutationRates {
+    (**self).rates(base, history)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{self, TestIndividual};
+
+  fn history(max_fitnesses: &[f64]) -> Vec<Statistics> {
+    max_fitnesses
+      .iter()
+      .map(|&fitness| Statistics::new(&[TestIndividual::new(&[0.0], fitness)]))
+      .collect()
+  }
+
+  #[test]
+  fn stagnation_raises_the_rates() {
+    let mut mutation = ScheduledMutation::new(GaussianMutation::new(0.1, 0.2), StagnationBoost::new(2, 3.0, 0.0));
+
+    mutation.adapt(&history(&[1.0, 2.0, 3.0]));
+    assert_eq!(mutation.rates(), MutationRates::new(0.1, 0.2));
+
+    mutation.adapt(&history(&[1.0, 2.0, 2.0, 2.0]));
+    let rates = mutation.rates();
+    assert!((rates.chance - 0.3).abs() < 1e-12);
+    assert!((rates.mag - 0.6).abs() < 1e-12);
+  }
+
+  #[test]
+  fn rates_follow_the_optimizer() {
+    let mut ga = GeneticAlgorithm::new(
+      RouletteWheelSelection::new(),
+      UniformCrossover::new(),
+      ScheduledMutation::new(GaussianMutation::new(0.1, 0.2), GenerationDecay::new(0.5, MutationRates::new(0.0, 0.0))),
+    );
+
+    Optimizer::adapt(&mut ga, &history(&[1.0, 1.0]));
+
+    let rates = ga.mutation_method.rates();
+    assert!((rates.chance - 0.025).abs() < 1e-12);
+    assert!((rates.mag - 0.05).abs() < 1e-12);
+  }
+
+  #[test]
+  fn one_fifth_rule_grows_above_a_fifth_and_shrinks_below() {
+    let rule = OneFifthRule::new(5, 0.5);
+    let base = MutationRates::new(0.1, 1.0);
+
+    // 5 generations after the first, so one full window
+    let improving = history(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let stagnant = history(&[1.0; 6]);
+    let one_in_five = history(&[1.0, 2.0, 2.0, 2.0, 2.0, 2.0]);
+
+    assert_eq!(rule.rates(base, &improving), MutationRates::new(0.1, 2.0));
+    assert_eq!(rule.rates(base, &stagnant), MutationRates::new(0.1, 0.5));
+    assert_eq!(rule.rates(base, &one_in_five), base);
+
+    // a partial window is not judged yet
+    assert_eq!(rule.rates(base, &improving[..5]), base);
+  }
+
+  #[test]
+  fn one_fifth_rule_judges_every_window() {
+    let mut rng = testing::rng();
+    let rule = OneFifthRule::new(4, 0.5);
+    let base = MutationRates::new(0.1, 1.0);
+
+    let mut fitness = 0.0;
+    let mut max_fitnesses = vec![fitness];
+    let mut expected = 1.0;
+
+    for _ in 0..10 {
+      let mut successes = 0;
+
+      for _ in 0..4 {
+        if rng.gen_bool(0.5) {
+          fitness += 1.0;
+          successes += 1;
+        }
+        max_fitnesses.push(fitness);
+      }
+
+      // a window of 4 is above a fifth with one success, below with none
+      expected *= if successes > 0 { 2.0 } else { 0.5 };
+    }
+
+    assert_eq!(rule.rates(base, &history(&max_fitnesses)).mag, expected);
+  }
+
+  #[test]
+  fn decay_never_goes_below_the_floor() {
+    let mut rng = testing::rng();
+    let floor = MutationRates::new(0.05, 0.1);
+    let decay = GenerationDecay::new(0.9, floor);
+
+    for _ in 0..100 {
+      let base = MutationRates::new(rng.gen_range(0.0..1.0), rng.gen_range(0.0..2.0));
+      let generations = rng.gen_range(0..200);
+      let rates = decay.rates(base, &history(&vec![1.0; generations]));
+
+      assert!(rates.chance >= floor.chance);
+      assert!(rates.mag >= floor.mag);
+      assert!(rates.chance <= base.chance.max(floor.chance));
+      assert!(rates.mag <= base.mag.max(floor.mag));
+    }
+
+    // long enough decays end on the floor
+    let rates = decay.rates(MutationRates::new(1.0, 1.0), &history(&[1.0; 100]));
+    assert_eq!(rates, floor);
+  }
+}
@@ -0,0 +1,349 @@
+use rand::seq::SliceRandom;
+
+use crate::*;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Species {
+  representative: Chromosome,
+  members: Vec<usize>, // indices into the speciated population
+  best_fitness: f64,
+  stagnant_generations: usize,
+  share: f64, // of the children expected this generation
+}
+
+impl Species {
+  fn new(representative: Chromosome) -> Self {
+    Self {
+      representative,
+      members: Vec::new(),
+      best_fitness: f64::NEG_INFINITY,
+      stagnant_generations: 0,
+      share: 0.0,
+    }
+  }
+
+  pub fn members(&self) -> &[usize] {
+    &self.members
+  }
+
+  pub fn best_fitness(&self) -> f64 {
+    self.best_fitness
+  }
+
+  pub fn stagnant_generations(&self) -> usize {
+    self.stagnant_generations
+  }
+
+  pub fn share(&self) -> f64 {
+    self.share
+  }
+}
+
+// clusters the population into species of compatible chromosomes, drops
+// species that stopped improving and shares fitness within each species: a
+// child's species is sampled in proportion to the species' total shared
+// fitness, which is its mean fitness, then both of its parents are picked
+// from that species by the wrapped method; genes are compared as
+// `Gene::value`s
+#[derive(Clone, Debug)]
+pub struct SpeciatedSelection<S> {
+  selection_method: S,
+  compatibility_threshold: f64, // max chromosome distance to a species' representative
+  stagnation_limit: usize, // generations without improvement before a species is culled
+  species: Vec<Species>,
+  population_len: usize, // of the population the species were clustered from, to catch others
+}
+
+impl<S> SpeciatedSelection<S>
+  where S: SelectionMethod, {
+
+  pub fn new(selection_method: S, compatibility_threshold: f64, stagnation_limit: usize) -> Self {
+    assert!(compatibility_threshold > 0.0);
+    assert!(stagnation_limit > 0);
+
+    Self {
+      selection_method,
+      compatibility_threshold,
+      stagnation_limit,
+      species: Vec::new(),
+      population_len: 0,
+    }
+  }
+
+  pub fn species(&self) -> &[Species] {
+    &self.species
+  }
+
+  fn cluster<I>(&mut self, population: &[I])
+    where I: Individual {
+    for species in &mut self.species {
+      species.members.clear();
+    }
+
+    for (index, individual) in population.iter().enumerate() {
+      let chromosome = individual.as_ref().values();
+
+      let compatible = self.species
+        .iter_mut()
+        .find(|species| species.representative.distance(&chromosome) < self.compatibility_threshold);
+
+      match compatible {
+        Some(species) => species.members.push(index),
+        None => {
+          let mut species = Species::new(chromosome);
+          species.members.push(index);
+          self.species.push(species);
+        }
+      }
+    }
+
+    self.species.retain(|species| !species.members.is_empty());
+
+    // the first member represents the species in the next generation
+    for species in &mut self.species {
+      species.representative = population[species.members[0]].as_ref().values();
+    }
+  }
+
+  fn update_stagnation<I>(&mut self, population: &[I])
+    where I: Individual {
+    for species in &mut self.species {
+      let best_fitness = species.members
+        .iter()
+        .map(|&index| population[index].fitness())
+        .fold(f64::NEG_INFINITY, f64::max);
+
+      if best_fitness > species.best_fitness {
+        species.best_fitness = best_fitness;
+        species.stagnant_generations = 0;
+      } else {
+        species.stagnant_generations += 1;
+      }
+    }
+  }
+
+  fn cull(&mut self) {
+    // the species holding the best individual always survives
+    let champion = self.species
+      .iter()
+      .enumerate()
+      .max_by(|(_, a), (_, b)| a.best_fitness.total_cmp(&b.best_fitness))
+      .map(|(index, _)| index);
+
+    let mut index = 0;
+    self.species.retain(|species| {
+      let keep = Some(index) == champion || species.stagnant_generations < self.stagnation_limit;
+      index += 1;
+      keep
+    });
+  }
+
+  fn share<I>(&mut self, population: &[I])
+    where I: Individual {
+    // the shared fitness of a member is its fitness divided by its species'
+    // size, so a species' total shared fitness is its mean fitness
+    let shares: Vec<_> = self.species
+      .iter()
+      .map(|species| {
+        let total = species.members
+          .iter()
+          .map(|&index| population[index].fitness())
+          .sum::<f64>();
+        (total / species.members.len() as f64).max(0.0)
+      })
+      .collect();
+
+    let total_share = shares.iter().sum::<f64>();
+    let uniform = 1.0 / self.species.len() as f64;
+
+    for (species, share) in self.species.iter_mut().zip(shares) {
+      species.share = if total_share > 0.0 { share / total_share } else { uniform };
+    }
+  }
+}
+
+impl<S> SelectionMethod for SpeciatedSelection<S>
+  where S: SelectionMethod, {
+
+  // within a species of the prepared population, or the whole population
+  // until one is prepared
+  fn select<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> &'a I
+    where I: Individual {
+    self.select_parents(rng, population)[0]
+  }
+
+  fn select_parents<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    if self.species.is_empty() {
+      return self.selection_method.select_parents(rng, population);
+    }
+
+    assert_eq!(population.len(), self.population_len, "Error - population was not the one prepared");
+
+    let species = self.species
+      .choose_weighted(rng, |species| species.share)
+      .expect("Error - species share the whole population");
+
+    let members: Vec<_> = species.members
+      .iter()
+      .map(|&index| Scored::Borrowed(&population[index], population[index].fitness()))
+      .collect();
+
+    self.selection_method
+      .select_parents_unprepared(rng, &members)
+      .map(Scored::individual)
+  }
+
+  // from the whole population, like a steady-state pool, which has no species
+  fn select_parents_unprepared<'a, I>(&self, rng: &mut impl RngCore, population: &'a [I]) -> [&'a I; 2]
+    where I: Individual {
+    self.selection_method.select_parents_unprepared(rng, population)
+  }
+
+  fn prepare<I>(&mut self, population: &[I])
+    where I: Individual {
+    assert!(!population.is_empty());
+
+    self.cluster(population);
+    self.update_stagnation(population);
+    self.cull();
+    self.share(population);
+
+    self.population_len = population.len();
+    self.selection_method.prepare(population);
+  }
+
+  // species carry their stagnation and representative over to the next
+  // generation
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    save(&(&self.species, self.population_len, self.selection_method.save_state()))
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    let (species, population_len, state) = load(state)?;
+    self.species = species;
+    self.population_len = population_len;
+    self.selection_method.load_state(state)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::*;
+
+  // two clusters far apart, the one around 10 twice as fit
+  fn population() -> Vec<TestIndividual> {
+    vec![
+      TestIndividual::new(&[0.0, 0.0], 1.0),
+      TestIndividual::new(&[0.1, 0.0], 1.0),
+      TestIndividual::new(&[10.0, 10.0], 2.0),
+      TestIndividual::new(&[10.0, 10.1], 2.0),
+      TestIndividual::new(&[0.0, 0.1], 1.0),
+      TestIndividual::new(&[10.1, 10.0], 2.0),
+    ]
+  }
+
+  #[test]
+  fn clusters_compatible_chromosomes() {
+    let mut selection = SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 10);
+    selection.prepare(&population());
+
+    let members: Vec<_> = selection.species().iter().map(Species::members).collect();
+    assert_eq!(members, vec![&[0, 1, 4][..], &[2, 3, 5][..]]);
+  }
+
+  #[test]
+  fn shares_children_by_mean_fitness() {
+    let mut selection = SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 10);
+    selection.prepare(&population());
+
+    let shares: Vec<_> = selection.species().iter().map(Species::share).collect();
+    assert!((shares[0] - 1.0 / 3.0).abs() < 1e-12);
+    assert!((shares[1] - 2.0 / 3.0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn parents_share_a_species() {
+    let mut rng = testing::rng();
+    let population = population();
+    let mut selection = SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 10);
+    selection.prepare(&population);
+
+    for _ in 0..100 {
+      let [parent_a, parent_b] = selection.select_parents(&mut rng, &population);
+      assert_eq!(parent_a.fitness, parent_b.fitness);
+    }
+  }
+
+  #[test]
+  fn unprepared_populations_have_no_species() {
+    let mut rng = testing::rng();
+    let population = population();
+    let mut selection = SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 10);
+    selection.prepare(&population);
+
+    // the same individuals as a pool, parents of different species can meet
+    let crossbred = (0..100).any(|_| {
+      let [parent_a, parent_b] = selection.select_parents_unprepared(&mut rng, &population);
+      parent_a.fitness != parent_b.fitness
+    });
+    assert!(crossbred);
+
+    // and so can they in a pool of another size
+    let pool = &population[..4];
+    assert!((0..100).any(|_| {
+      let [parent_a, parent_b] = selection.select_parents_unprepared(&mut rng, pool);
+      parent_a.fitness != parent_b.fitness
+    }));
+  }
+
+  #[test]
+  #[should_panic(expected = "not the one prepared")]
+  fn other_populations_must_be_selected_from_unprepared() {
+    let population = population();
+    let mut selection = SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 10);
+    selection.prepare(&population);
+
+    selection.select_parents(&mut testing::rng(), &population[..4]);
+  }
+
+  #[test]
+  fn culls_stagnant_species_but_the_best() {
+    let population = population();
+    let mut selection = SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 2);
+
+    // best fitnesses are set in the first generation, and stay put for two more
+    for _ in 0..3 {
+      selection.prepare(&population);
+    }
+
+    assert_eq!(selection.species().len(), 1);
+    assert_eq!(selection.species()[0].best_fitness(), 2.0);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn boxed_species_are_saved_and_loaded() {
+    let speciated = || -> Box<dyn DynSelectionMethod> {
+      Box::new(SpeciatedSelection::new(RouletteWheelSelection::new(), 1.0, 10))
+    };
+    let mut selection = speciated();
+    selection.prepare(&population());
+
+    let mut loaded = speciated();
+    loaded.load_state(selection.save_state()).unwrap();
+
+    assert_ne!(selection.save_state(), serde_json::Value::Null);
+    assert_eq!(loaded.save_state(), selection.save_state());
+  }
+
+  #[test]
+  fn is_sync() {
+    fn assert_sync<T: Sync>() {}
+    assert_sync::<SpeciatedSelection<RouletteWheelSelection>>();
+  }
+}
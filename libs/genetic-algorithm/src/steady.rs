@@ -0,0 +1,233 @@
+use crate::*;
+
+// age is counted alps style: fresh random individuals start at 0 and children
+// are one older than their oldest parent
+pub trait AgedIndividual: Individual {
+  fn age(&self) -> usize;
+}
+
+impl<I> AgedIndividual for Scored<'_, I>
+  where I: AgedIndividual, {
+
+  fn age(&self) -> usize {
+    match self {
+      Self::Borrowed(individual, _) => individual.age(),
+      Self::Offspring(_) => 0,
+    }
+  }
+}
+
+// picks which individual a steady-state child replaces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+  Worst,
+  Oldest,
+  TournamentLoser { size: usize }, // least fit of `size` random individuals
+}
+
+impl ReplacementPolicy {
+  pub fn victim<I>(&self, rng: &mut impl RngCore, population: &[I]) -> usize
+    where I: AgedIndividual {
+    let candidates: Vec<_> = (0..population.len()).collect();
+    self.victim_among(rng, population, &candidates)
+  }
+
+  // like `victim`, but only the given indices may be replaced
+  pub fn victim_among<I>(&self, rng: &mut impl RngCore, population: &[I], candidates: &[usize]) -> usize
+    where I: AgedIndividual {
+    assert!(!candidates.is_empty());
+
+    let by_fitness = |&a: &usize, &b: &usize| population[a].fitness().total_cmp(&population[b].fitness());
+
+    match *self {
+      Self::Worst => candidates
+        .iter()
+        .copied()
+        .min_by(by_fitness)
+        .unwrap_or_default(),
+      Self::Oldest => candidates
+        .iter()
+        .copied()
+        .max_by_key(|&index| population[index].age())
+        .unwrap_or_default(),
+      Self::TournamentLoser { size } => {
+        assert!(size > 0);
+        (0..size)
+          .map(|_| candidates[gen_index(rng, candidates.len())])
+          .min_by(by_fitness)
+          .unwrap_or_default()
+      }
+    }
+  }
+}
+
+// hornby's age-layered population structure: layer i only holds individuals
+// younger than age_gap * (i + 1)^2, and the last layer has no limit
+#[derive(Clone, Debug)]
+pub struct AgeLayers {
+  age_gap: usize,
+  layers: usize,
+}
+
+impl AgeLayers {
+  pub fn new(age_gap: usize, layers: usize) -> Self {
+    assert!(age_gap > 0);
+    assert!(layers > 0);
+    Self { age_gap, layers }
+  }
+
+  pub fn layers(&self) -> usize {
+    self.layers
+  }
+
+  pub fn limit(&self, layer: usize) -> Option<usize> {
+    (layer + 1 < self.layers).then(|| self.age_gap * (layer + 1).pow(2))
+  }
+
+  pub fn layer(&self, age: usize) -> usize {
+    (0..self.layers)
+      .find(|&layer| self.limit(layer).is_none_or(|limit| age < limit))
+      .unwrap_or(self.layers - 1)
+  }
+
+  pub fn members<I>(&self, population: &[I], layer: usize) -> Vec<usize>
+    where I: AgedIndividual {
+    (0..population.len())
+      .filter(|&index| self.layer(population[index].age()) == layer)
+      .collect()
+  }
+
+  // indices of individuals that may parent a child of `layer`, which are
+  // those of the layer itself and of the layer below it
+  pub fn parents<I>(&self, population: &[I], layer: usize) -> Vec<usize>
+    where I: AgedIndividual {
+    (0..population.len())
+      .filter(|&index| {
+        let own = self.layer(population[index].age());
+        own == layer || own + 1 == layer
+      })
+      .collect()
+  }
+}
+
+impl<S, C, M> GeneticAlgorithm<S, C, M>
+  where S: SelectionMethod, {
+
+  // breed a single child for steady-state evolution, returned with its age
+  pub fn offspring<I>(&self, rng: &mut impl RngCore, population: &[I]) -> (I, usize)
+    where I: AgedIndividual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    assert!(!population.is_empty());
+
+    let scaled = self.scaled(population);
+    let [parent_a, parent_b] = self.selection_method
+      .select_parents_unprepared(rng, &scaled)
+      .map(Scored::individual);
+
+    let mut child = self.crossover_method.crossover(rng, parent_a.as_ref(), parent_b.as_ref());
+    self.mutation_method.mutate(rng, &mut child);
+
+    (I::from(child), parent_a.age().max(parent_b.age()) + 1)
+  }
+
+  // breed a child of `layer` from that layer and the one below, if any parents are there
+  pub fn layered_offspring<I>(&self, rng: &mut impl RngCore, layers: &AgeLayers, layer: usize, population: &[I]) -> Option<(I, usize)>
+    where I: AgedIndividual,
+          C: CrossoverMethod<I::Gene>,
+          M: MutationMethod<I::Gene>, {
+    let parents: Vec<_> = layers
+      .parents(population, layer)
+      .into_iter()
+      .map(|index| Scored::Borrowed(&population[index], population[index].fitness()))
+      .collect();
+
+    if parents.is_empty() {
+      return None;
+    }
+
+    match self.offspring(rng, &parents) {
+      (Scored::Offspring(child), age) => Some((I::from(child), age)),
+      (Scored::Borrowed(..), _) => unreachable!(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::{rng, TestIndividual};
+
+  // fitness 3, 1, 4, 2 at ages 0, 5, 2, 9
+  fn population() -> Vec<TestIndividual> {
+    [(3.0, 0), (1.0, 5), (4.0, 2), (2.0, 9)]
+      .into_iter()
+      .map(|(fitness, age)| TestIndividual::new(&[fitness], fitness).with_age(age))
+      .collect()
+  }
+
+  #[test]
+  fn replaces_the_worst_or_the_oldest() {
+    let population = population();
+
+    assert_eq!(ReplacementPolicy::Worst.victim(&mut rng(), &population), 1);
+    assert_eq!(ReplacementPolicy::Oldest.victim(&mut rng(), &population), 3);
+    assert_eq!(ReplacementPolicy::Worst.victim_among(&mut rng(), &population, &[0, 2, 3]), 3);
+  }
+
+  #[test]
+  fn tournament_loser_is_among_the_candidates() {
+    let population = population();
+    let mut rng = rng();
+
+    for _ in 0..50 {
+      let victim = ReplacementPolicy::TournamentLoser { size: 2 }.victim_among(&mut rng, &population, &[0, 2]);
+      assert!(victim == 0 || victim == 2);
+    }
+
+    // a tournament over everyone always finds the worst, given enough draws
+    let victim = ReplacementPolicy::TournamentLoser { size: 200 }.victim(&mut rng, &population);
+    assert_eq!(victim, 1);
+  }
+
+  #[test]
+  fn layers_grow_quadratically() {
+    let layers = AgeLayers::new(5, 3);
+
+    assert_eq!(layers.limit(0), Some(5));
+    assert_eq!(layers.limit(1), Some(20));
+    assert_eq!(layers.limit(2), None);
+    assert_eq!([0, 4, 5, 19, 20, 1000].map(|age| layers.layer(age)), [0, 0, 1, 1, 2, 2]);
+  }
+
+  #[test]
+  fn layers_breed_from_themselves_and_below() {
+    let layers = AgeLayers::new(3, 3);
+    let population = population();
+
+    assert_eq!(layers.members(&population, 0), [0, 2]);
+    assert_eq!(layers.members(&population, 1), [1, 3]);
+    assert_eq!(layers.parents(&population, 0), [0, 2]);
+    assert_eq!(layers.parents(&population, 1), [0, 1, 2, 3]);
+    assert!(layers.parents(&population, 2).iter().all(|&index| layers.layer(population[index].age) >= 1));
+  }
+
+  #[test]
+  fn children_are_a_generation_older_than_their_oldest_parent() {
+    let ga = GeneticAlgorithm::new(RouletteWheelSelection::new(), UniformCrossover::new(), GaussianMutation::new(0.5, 0.5));
+    let population = population();
+    let mut rng = rng();
+
+    for _ in 0..20 {
+      let (_, age): (TestIndividual, _) = ga.offspring(&mut rng, &population);
+      assert!([1, 3, 6, 10].contains(&age));
+    }
+
+    // the top layer of a single-layer-deep population has nobody to breed from
+    let layers = AgeLayers::new(100, 3);
+    assert!(ga.layered_offspring::<TestIndividual>(&mut rng, &layers, 2, &population).is_none());
+
+    let (_, age) = ga.layered_offspring::<TestIndividual>(&mut rng, &layers, 0, &population).unwrap();
+    assert!([1, 3, 6, 10].contains(&age));
+  }
+}
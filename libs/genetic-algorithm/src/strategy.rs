@@ -0,0 +1,158 @@
+use rand::seq::SliceRandom;
+
+use crate::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Replacement {
+  Plus, // (mu + lambda), parents compete with their offspring
+  Comma, // (mu, lambda), only offspring survive
+}
+
+// classic evolution strategy: lambda offspring (the population size) are
+// mutated from uniformly picked parents, and the best mu survive as the next
+// parents; pairs well with `SelfAdaptiveMutation`; with comma replacement and
+// fewer offspring than mu, every offspring survives
+#[derive(Clone, Debug)]
+pub struct EvolutionStrategy<M> {
+  mu: usize,
+  replacement: Replacement,
+  mutation_method: M,
+  parents: Vec<Evaluated>,
+}
+
+impl<M> EvolutionStrategy<M>
+  where M: MutationMethod, {
+
+  pub fn new(mu: usize, replacement: Replacement, mutation_method: M) -> Self {
+    assert!(mu > 0);
+
+    Self {
+      mu,
+      replacement,
+      mutation_method,
+      parents: Vec::new(),
+    }
+  }
+
+  pub fn parents(&self) -> impl Iterator<Item = (&Chromosome, f64)> {
+    self.parents
+      .iter()
+      .map(|parent| (&parent.chromosome, parent.fitness))
+  }
+
+  fn select<I>(&mut self, population: &[I])
+    where I: Individual<Gene = f64> {
+    let mut candidates: Vec<_> = population.iter().map(Evaluated::new).collect();
+
+    if self.replacement == Replacement::Plus {
+      candidates.append(&mut self.parents);
+    }
+
+    candidates.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+    candidates.truncate(self.mu);
+
+    self.parents = candidates;
+  }
+}
+
+impl<M> Optimizer for EvolutionStrategy<M>
+  where M: MutationMethod, {
+
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where I: Individual<Gene = f64> {
+    assert!(!population.is_empty());
+
+    self.select(population);
+
+    let offspring = (0..population.len())
+      .map(|_| {
+        let parent = self.parents
+          .choose(rng)
+          .expect("Error - no parents");
+
+        let mut child = parent.chromosome.clone();
+        self.mutation_method.mutate(rng, &mut child);
+
+        I::from(child)
+      })
+      .collect();
+
+    let stats = Statistics::new(population);
+
+    (offspring, stats)
+  }
+
+  fn adapt(&mut self, history: &[Statistics]) {
+    self.mutation_method.adapt(history);
+  }
+
+  #[cfg(feature = "serde")]
+  fn save_state(&self) -> serde_json::Value {
+    save(&self.parents)
+  }
+
+  #[cfg(feature = "serde")]
+  fn load_state(&mut self, state: serde_json::Value) -> std::io::Result<()> {
+    self.parents = load(state)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::testing::TestIndividual;
+
+  fn population(fitnesses: &[f64]) -> Vec<TestIndividual> {
+    fitnesses
+      .iter()
+      .map(|&fitness| TestIndividual::new(&[fitness], fitness))
+      .collect()
+  }
+
+  fn parent_fitnesses<M: MutationMethod>(es: &EvolutionStrategy<M>) -> Vec<f64> {
+    es.parents().map(|(_, fitness)| fitness).collect()
+  }
+
+  #[test]
+  fn plus_keeps_parents_that_beat_their_offspring() {
+    let mut rng = crate::testing::rng();
+    let mut es = EvolutionStrategy::new(2, Replacement::Plus, GaussianMutation::new(0.5, 0.1));
+
+    es.next_generation(&mut rng, &population(&[5.0, 1.0, 4.0]));
+    assert_eq!(parent_fitnesses(&es), [5.0, 4.0]);
+
+    es.next_generation(&mut rng, &population(&[3.0, 2.0, 1.0]));
+    assert_eq!(parent_fitnesses(&es), [5.0, 4.0]);
+  }
+
+  #[test]
+  fn comma_keeps_only_offspring() {
+    let mut rng = crate::testing::rng();
+    let mut es = EvolutionStrategy::new(2, Replacement::Comma, GaussianMutation::new(0.5, 0.1));
+
+    es.next_generation(&mut rng, &population(&[5.0, 1.0, 4.0]));
+    es.next_generation(&mut rng, &population(&[3.0, 2.0, 1.0]));
+    assert_eq!(parent_fitnesses(&es), [3.0, 2.0]);
+  }
+
+  #[test]
+  fn comma_with_fewer_offspring_than_mu_keeps_them_all() {
+    let mut rng = crate::testing::rng();
+    let mut es = EvolutionStrategy::new(5, Replacement::Comma, GaussianMutation::new(0.5, 0.1));
+
+    let (offspring, _) = es.next_generation(&mut rng, &population(&[1.0, 3.0, 2.0]));
+    assert_eq!(parent_fitnesses(&es), [3.0, 2.0, 1.0]);
+    assert_eq!(offspring.len(), 3);
+  }
+
+  #[test]
+  fn offspring_descend_from_parents() {
+    let mut rng = crate::testing::rng();
+    let mut es = EvolutionStrategy::new(1, Replacement::Plus, GaussianMutation::new(0.0, 0.0));
+
+    let (offspring, _) = es.next_generation(&mut rng, &population(&[1.0, 7.0, 3.0]));
+    assert!(offspring.iter().all(|child| child.chromosome[0] == 7.0));
+  }
+}
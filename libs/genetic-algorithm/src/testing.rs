@@ -0,0 +1,78 @@
+use crate::*;
+
+// individual for unit tests, scored by whatever the test sets
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TestIndividual {
+  pub(crate) chromosome: Chromosome,
+  pub(crate) fitness: f64,
+  pub(crate) id: u64,
+  pub(crate) behaviour: Option<Vec<f64>>,
+  pub(crate) age: usize,
+}
+
+impl TestIndividual {
+  pub(crate) fn new(genes: &[f64], fitness: f64) -> Self {
+    Self {
+      chromosome: genes.iter().copied().collect(),
+      fitness,
+      ..Self::default()
+    }
+  }
+
+  pub(crate) fn with_behaviour(mut self, behaviour: &[f64]) -> Self {
+    self.behaviour = Some(behaviour.to_vec());
+    self
+  }
+
+  pub(crate) fn with_age(mut self, age: usize) -> Self {
+    self.age = age;
+    self
+  }
+}
+
+impl Individual for TestIndividual {
+  type Gene = f64;
+
+  fn fitness(&self) -> f64 {
+    self.fitness
+  }
+
+  fn behaviour(&self) -> Option<Vec<f64>> {
+    self.behaviour.clone()
+  }
+}
+
+impl AgedIndividual for TestIndividual {
+  fn age(&self) -> usize {
+    self.age
+  }
+}
+
+impl TrackedIndividual for TestIndividual {
+  fn id(&self) -> u64 {
+    self.id
+  }
+
+  fn set_id(&mut self, id: u64) {
+    self.id = id;
+  }
+}
+
+impl AsRef<Chromosome> for TestIndividual {
+  fn as_ref(&self) -> &Chromosome {
+    &self.chromosome
+  }
+}
+
+impl From<Chromosome> for TestIndividual {
+  fn from(chromosome: Chromosome) -> Self {
+    Self {
+      chromosome,
+      ..Self::default()
+    }
+  }
+}
+
+pub(crate) fn rng() -> rand_chacha::ChaCha8Rng {
+  rand::SeedableRng::seed_from_u64(42)
+}
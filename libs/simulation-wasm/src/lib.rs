@@ -1,8 +1,10 @@
-use serde::Serialize;
+use serde::{Serialize, Deserialize};
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 use wasm_bindgen::prelude::*;
 use gloo_utils::format::JsValueSerdeExt;
 
+use std::ops::ControlFlow;
 use std::panic;
 
 use lib_simulation as sim;
@@ -38,13 +40,13 @@ pub struct Pipe {
   pub offy: f64,
 }
 
-impl From<&sim::Pipe> for Pipe {
-  fn from(pipe: &sim::Pipe) -> Self {
+impl Pipe {
+  fn new(pipe: &sim::Pipe, config: &sim::SimulationConfig) -> Self {
     Self {
       x: pipe.position().x,
       y: pipe.position().y,
-      offx: sim::PIPE_OFFSET_X,
-      offy: sim::PIPE_OFFSET_Y,
+      offx: config.pipe_offset_x,
+      offy: config.pipe_offset_y,
     }
   }
 }
@@ -64,7 +66,7 @@ impl From<&sim::World> for World {
 
     let pipes = world
       .pipes()
-      .map(Pipe::from)
+      .map(|pipe| Pipe::new(pipe, world.config()))
       .collect();
 
     Self { 
@@ -74,22 +76,126 @@ impl From<&sim::World> for World {
   }
 }
 
+#[derive(Clone, Debug, Serialize)]
+pub struct Statistics {
+  pub min_fitness: f64,
+  pub max_fitness: f64,
+  pub avg_fitness: f64,
+  pub median_fitness: f64,
+  pub std_dev_fitness: f64,
+  pub p10_fitness: f64,
+  pub p25_fitness: f64,
+  pub p75_fitness: f64,
+  pub p90_fitness: f64,
+  pub diversity: f64,
+  pub best_index: usize,
+}
+
+impl From<&sim::ga::Statistics> for Statistics {
+  fn from(stats: &sim::ga::Statistics) -> Self {
+    Self {
+      min_fitness: stats.min_fitness(),
+      max_fitness: stats.max_fitness(),
+      avg_fitness: stats.avg_fitness(),
+      median_fitness: stats.median_fitness(),
+      std_dev_fitness: stats.std_dev_fitness(),
+      p10_fitness: stats.percentile(10.0),
+      p25_fitness: stats.percentile(25.0),
+      p75_fitness: stats.percentile(75.0),
+      p90_fitness: stats.percentile(90.0),
+      diversity: stats.diversity(),
+      best_index: stats.best_index(),
+    }
+  }
+}
+
+// calls back into js with the generation and its `Statistics` once it is
+// evolved; returning `false` stops evolution
+struct JsObserver(js_sys::Function);
+
+// wasm runs on a single thread here, so the callback never actually crosses
+// one; `Simulation` only needs its observer to be `Send` for native callers
+unsafe impl Send for JsObserver {}
+
+impl sim::ga::Observer for JsObserver {
+  fn generation_end(&mut self, generation: usize, stats: &sim::ga::Statistics) -> ControlFlow<()> {
+    let stats = <JsValue as JsValueSerdeExt>::from_serde(&Statistics::from(stats)).unwrap();
+    let result = self.0
+      .call2(&JsValue::NULL, &JsValue::from_f64(generation as f64), &stats)
+      .unwrap_or_else(|err| {
+        // a throwing callback must not take evolution down with it
+        web_sys::console::error_2(&"Error - generation observer threw".into(), &err);
+        JsValue::UNDEFINED
+      });
+
+    match result.as_bool() {
+      Some(false) => ControlFlow::Break(()),
+      _ => ControlFlow::Continue(()),
+    }
+  }
+}
+
+// genetic algorithm operators alongside an optional `simulation` section of
+// physics and world settings; an `optimizer` section swaps the genetic
+// algorithm for another optimizer
+#[derive(Clone, Debug, Default, Deserialize)]
+struct Config {
+  #[serde(flatten)]
+  genetic_algorithm: sim::ga::GeneticAlgorithmConfig,
+  #[serde(default)]
+  optimizer: Option<sim::ga::OptimizerConfig>,
+  #[serde(default)]
+  simulation: sim::SimulationConfig,
+}
+
 #[wasm_bindgen]
 pub struct Simulation {
-  rng: ThreadRng,
-  sim: sim::Simulation
+  rng: ChaCha8Rng,
+  sim: sim::Simulation<sim::ga::DynOptimizer>,
 }
 
 #[wasm_bindgen]
 impl Simulation {
   #[wasm_bindgen(constructor)]
   pub fn new() -> Self {
+    Self::from_config(Config::default(), None).expect("Error - default config is valid")
+  }
+
+  // replays exactly like the native `lib_simulation::Simulation::from_seed`
+  // without the parallel feature, see the tests below
+  pub fn from_seed(seed: u64) -> Self {
+    Self::from_config(Config::default(), Some(seed)).expect("Error - default config is valid")
+  }
+
+  // operators picked in the ui, e.g. { selection: "RouletteWheel", crossover: "Uniform",
+  // mutation: { Gaussian: { chance: 0.015, mag: 0.3 } }, simulation: { gravity: -0.0008,
+  // fitness: "TicksAlive" } } or { optimizer: { CmaEs: { sigma: 0.5 } } }, seeded
+  // when a seed is given
+  pub fn with_config(config: JsValue, seed: Option<u64>) -> Result<Simulation, JsValue> {
+    let config = <JsValue as JsValueSerdeExt>::into_serde(&config)
+      .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    Self::from_config(config, seed)
+  }
+
+  fn from_config(config: Config, seed: Option<u64>) -> Result<Self, JsValue> {
     panic::set_hook(Box::new(console_error_panic_hook::hook));
 
-    let mut rng = thread_rng();
-    let sim = sim::Simulation::random(&mut rng);
+    config.simulation
+      .validate()
+      .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    let optimizer = config.optimizer
+      .unwrap_or(config.genetic_algorithm.into())
+      .build()
+      .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    let mut rng = match seed {
+      Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+      None => ChaCha8Rng::from_entropy(),
+    };
+    let sim = sim::Simulation::with_config(&mut rng, optimizer, config.simulation);
 
-    Self { rng, sim }
+    Ok(Self { rng, sim })
   }
 
   pub fn step(&mut self) -> bool {
@@ -101,6 +207,26 @@ impl Simulation {
     self.sim.tick_count() == 0
   }
 
+  // built-in fitness function birds are scored with from now on, e.g. "PipesPassed"
+  // or { FlapEconomy: { flap_cost: 0.5 } }
+  pub fn set_fitness(&mut self, config: JsValue) -> Result<(), JsValue> {
+    let config: sim::FitnessConfig = <JsValue as JsValueSerdeExt>::into_serde(&config)
+      .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    self.sim
+      .set_fitness_config(config)
+      .map_err(|err| JsValue::from_str(&err.to_string()))
+  }
+
+  // e.g. sim.on_generation_end((generation, stats) => plot(stats))
+  pub fn on_generation_end(&mut self, callback: js_sys::Function) {
+    self.sim.observe(JsObserver(callback));
+  }
+
+  pub fn stopped(&self) -> bool {
+    self.sim.is_stopped()
+  }
+
   pub fn world(&self) -> JsValue {
     let world = World::from(self.sim.world());
     <JsValue as JsValueSerdeExt>::from_serde(&world).unwrap()
@@ -109,10 +235,56 @@ impl Simulation {
   pub fn ticks(&self) -> JsValue {
     JsValue::from_f64(self.sim.tick_count() as f64)
   }
+
+  // [pipes passed, distance per flap, negated brain complexity] of every bird
+  // on the last generation's pareto front
+  pub fn pareto_front(&self) -> JsValue {
+    <JsValue as JsValueSerdeExt>::from_serde(self.sim.pareto_front()).unwrap()
+  }
+
+  pub fn history(&self) -> JsValue {
+    let history: Vec<_> = self.sim
+      .history()
+      .iter()
+      .map(Statistics::from)
+      .collect();
+    <JsValue as JsValueSerdeExt>::from_serde(&history).unwrap()
+  }
 }
 
 impl Default for Simulation {
   fn default() -> Self {
     Self::new()
   }
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+  use super::*;
+  use wasm_bindgen_test::wasm_bindgen_test;
+
+  // keep in line with the sequential `FINGERPRINT` of lib-simulation's
+  // tests/determinism.rs
+  const FINGERPRINT: u64 = 14_914_109_139_417_448_003;
+
+  #[wasm_bindgen_test]
+  fn replays_like_native() {
+    let mut simulation = Simulation::from_seed(7);
+    simulation.sim.run(&mut simulation.rng, sim::ga::MaxGenerations(5));
+
+    assert_eq!(simulation.sim.fingerprint(), FINGERPRINT);
+  }
+
+  #[wasm_bindgen_test]
+  fn stepping_replays_like_running() {
+    let mut stepped = Simulation::from_seed(7);
+    while stepped.sim.generations() < 2 {
+      stepped.step();
+    }
+
+    let mut run = Simulation::from_seed(7);
+    run.sim.run(&mut run.rng, sim::ga::MaxGenerations(2));
+
+    assert_eq!(stepped.sim.fingerprint(), run.sim.fingerprint());
+  }
+}
@@ -0,0 +1,34 @@
+// wall time of headless generations at growing population sizes, where
+// flying the episode dominates; compare with and without the parallel feature:
+//   cargo bench --bench evaluate
+//   cargo bench --bench evaluate --features parallel
+use lib_simulation as sim;
+use sim::ga;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use std::time::Instant;
+
+const GENERATIONS: usize = 5;
+
+fn main() {
+  for population_count in [70, 280, 1120] {
+    let config = sim::SimulationConfig {
+      population_count,
+      ..sim::SimulationConfig::default()
+    };
+
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut simulation = sim::Simulation::with_config(&mut rng, ga::DefaultGeneticAlgorithm::default(), config);
+
+    let start = Instant::now();
+    simulation.run(&mut rng, ga::MaxGenerations(GENERATIONS));
+    let elapsed = start.elapsed();
+
+    println!(
+      "{population_count:>5} birds: {:>8.2} ms per generation",
+      elapsed.as_secs_f64() * 1000.0 / GENERATIONS as f64,
+    );
+  }
+}
@@ -0,0 +1,58 @@
+use lib_simulation as sim;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+// hyperparameter search over the default search space:
+//   cargo run --release --bin sweep grid [generations] [seeds] [results.csv|results.json]
+//   cargo run --release --bin sweep random <settings> [generations] [seeds] [results]
+//   cargo run --release --bin sweep meta <population> <meta generations> [generations] [seeds] [results]
+// results go to stdout as csv unless a file is given
+fn main() -> io::Result<()> {
+  let mut args = env::args().skip(1);
+  let mode = args.next().unwrap_or_else(|| "grid".into());
+  let mut number = |default| args.next().and_then(|arg| arg.parse().ok()).unwrap_or(default);
+
+  let space = sim::SearchSpace::default();
+  let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+  let (settings, meta) = match mode.as_str() {
+    "grid" => (space.grid(), None),
+    "random" => (space.random(&mut rng, number(20)), None),
+    "meta" => (Vec::new(), Some((number(10), number(5)))),
+    _ => panic!("Error - unknown mode {mode}, expected grid, random or meta"),
+  };
+
+  let sweep = sim::Sweep {
+    generations: number(100),
+    seeds: (0..number(3) as u64).collect(),
+    config: sim::SimulationConfig::default(),
+  };
+  let output = args.next();
+
+  let progress = |trial: &sim::Trial| {
+    eprintln!(
+      "{:?} seed {} best {:.2} avg {:.2} in {:.1}s",
+      trial.params,
+      trial.seed,
+      trial.best_fitness,
+      trial.final_avg_fitness,
+      trial.seconds,
+    );
+  };
+
+  let trials = match meta {
+    Some((population, generations)) => sweep.meta_evolve(&mut rng, &space, population, generations, progress),
+    None => sweep.run(&settings, progress),
+  };
+
+  match output {
+    Some(path) if path.ends_with(".json") => sim::write_json(&trials, BufWriter::new(File::create(path)?)),
+    Some(path) => sim::write_csv(&trials, BufWriter::new(File::create(path)?)),
+    None => sim::write_csv(&trials, io::stdout().lock()),
+  }
+}
@@ -0,0 +1,75 @@
+use lib_simulation as sim;
+use sim::ga::{self, Termination};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use std::env;
+use std::ops::ControlFlow;
+use std::path::Path;
+use std::time::Duration;
+
+// headless training: cargo run --release --bin train [max generations] [max seconds] [checkpoint] [config.toml|config.json]
+// an existing checkpoint is resumed from, and the checkpoint is rewritten once training stops;
+// a fresh run takes its physics and world settings from the config file, if given,
+// a resumed one keeps those it was checkpointed with
+fn main() {
+  let mut args = env::args().skip(1);
+  let generations = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(500);
+  let seconds = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(600);
+  let checkpoint = args.next();
+  let config_path = args.next();
+
+  let termination = ga::MaxGenerations(generations)
+    .or(ga::TimeBudget(Duration::from_secs(seconds)))
+    .or(ga::Stagnation { generations: 100, epsilon: 0.0 })
+    .or(ga::DiversityCollapse(1e-3));
+
+  let (mut simulation, mut rng) = match &checkpoint {
+    Some(path) if Path::new(path).exists() => {
+      // the checkpoint carries the settings it was trained with
+      if let Some(config_path) = &config_path {
+        eprintln!("Warning - resuming from {path}, ignoring config {config_path}");
+      }
+
+      sim::Simulation::load_checkpoint(path, ga::DefaultGeneticAlgorithm::default())
+        .expect("Error - could not load checkpoint")
+    }
+    _ => {
+      let config = config_path.map_or_else(sim::SimulationConfig::default, |path| {
+        sim::SimulationConfig::load(path).expect("Error - could not load config")
+      });
+      let mut rng = ChaCha8Rng::from_entropy();
+      (sim::Simulation::with_config(&mut rng, ga::DefaultGeneticAlgorithm::default(), config), rng)
+    }
+  };
+
+  simulation.observe(ga::OnGenerationEnd(|generation, stats: &ga::Statistics| {
+    println!(
+      "{:>5} min {:>8.2} avg {:>8.2} max {:>8.2} diversity {:>6.3}",
+      generation,
+      stats.min_fitness(),
+      stats.avg_fitness(),
+      stats.max_fitness(),
+      stats.diversity(),
+    );
+
+    ControlFlow::Continue(())
+  }));
+
+  let summary = simulation.run(&mut rng, termination);
+
+  println!(
+    "stopped by {:?} after {} generations in {:.1?}, best fitness {:.2}",
+    summary.reason,
+    summary.generations,
+    summary.elapsed,
+    summary.best_fitness,
+  );
+
+  if let Some(path) = &checkpoint {
+    simulation
+      .save_checkpoint(path, &rng)
+      .expect("Error - could not save checkpoint");
+  }
+}
@@ -3,8 +3,6 @@ use nalgebra as na;
 
 use crate::*;
 
-pub const BIRD_X: f64 = 0.15;
-
 pub const BIRD_OFFSET_X: f64 = 0.012;
 pub const BIRD_OFFSET_Y: f64 = 0.012;
 
@@ -13,60 +11,110 @@ pub const BIRD_OFFSET_Y: f64 = 0.012;
 pub struct Bird {
   pub position: na::Point2<f64>,
   pub dy: f64,
-  pub fit_distance: f64,
-  pub passes: usize,
-  jump_timer: usize,
+  pub fit_distance: f64, // as scored by the world's `FitnessFunction`
+  pub pass_ticks: Vec<usize>, // tick every pipe was cleared at, in order
+  pub distance: f64,
+  pub gap_distance: f64, // height from the centre of the next gap at death
+  pub flaps: usize,
+  pub ticks: usize, // ticks alive
+  pub(crate) jump_timer: usize,
+  pub(crate) jump_cooldown: usize,
+  pub(crate) index: usize, // position within the population
+  pub(crate) age: usize, // generations since its oldest ancestor was random
+  pub(crate) id: u64, // lineage id, only meaningful while lineage is tracked
+  pub(crate) grace: usize, // ticks left in which pipes cannot hit it
+  pub(crate) violation: f64, // how far its bred weights lay outside of `weight_bounds`
+  pub(crate) alive: bool,
   pub(crate) eye: Eye,
   pub(crate) brain: Brain,
 }
 
 impl Bird {
-  pub fn random(rng: &mut impl RngCore) -> Self {
-    Self::new(Brain::random(rng), rng)
+  pub fn random(rng: &mut impl RngCore, config: &SimulationConfig) -> Self {
+    Self::new(Brain::random(rng), rng, config)
   }
 
-  pub fn new(brain: Brain, rng: &mut impl RngCore) -> Self {
+  pub fn new(brain: Brain, rng: &mut impl RngCore, config: &SimulationConfig) -> Self {
     Self { 
-      position: na::Point2::new(BIRD_X, rng.gen()),
+      position: na::Point2::new(config.bird_x, rng.gen()),
       dy: 0.005,
-      fit_distance: 0.01,
-      passes: 0,
+      fit_distance: 0.0,
+      pass_ticks: Vec::new(),
+      distance: 0.0,
+      gap_distance: 0.0,
+      flaps: 0,
+      ticks: 0,
       jump_timer: 0,
-      eye: Eye::new(),
+      jump_cooldown: config.jump_cooldown,
+      index: 0,
+      age: 0,
+      id: 0,
+      grace: 0,
+      violation: 0.0,
+      alive: true,
+      eye: Eye::new([true; EYE_INPUTS]),
       brain,
     }
   }
 
-  pub(crate) fn from_chromosome(chromosome: ga::Chromosome, rng: &mut impl RngCore) -> Self {
+  pub(crate) fn from_chromosome(chromosome: ga::Chromosome, rng: &mut impl RngCore, config: &SimulationConfig) -> Self {
     let brain = chromosome.into();
-    Self::new(brain, rng)
+    Self::new(brain, rng, config)
+  }
+
+  pub(crate) fn set_traits(&mut self, traits: &BirdTraits) {
+    self.jump_cooldown = traits.jump_cooldown;
+    self.eye = Eye::new(traits.eye);
+  }
+
+  pub(crate) fn collision(&self, closest_pipe: Option<&Pipe>, config: &SimulationConfig) -> bool {
+    self.collision_screen() || (self.grace == 0 && self.collision_pipe(closest_pipe, config))
   }
 
-  pub(crate) fn collision(&self, closest_pipe: Option<&Pipe>) -> bool {
-    self.collision_screen() || self.collision_pipe(closest_pipe)
+  // a bird born mid-flight may appear inside a pipe, so spare it until the
+  // pipe it could have appeared in has passed
+  pub(crate) fn spare(&mut self, config: &SimulationConfig) {
+    let width = 2.0 * (config.pipe_offset_x + BIRD_OFFSET_X);
+    self.grace = (width / config.pipe_dx).ceil() as usize;
   }
 
-  pub(crate) fn decision(&mut self, closest_pipe: na::Point2<f64>) {
-    let vision = self.eye.step_vision(self.dy, self.position, closest_pipe);
+  pub(crate) fn decision(&mut self, closest_pipe: na::Point2<f64>, config: &SimulationConfig) {
+    let vision = self.eye.step_vision(self.dy, self.position, closest_pipe, config);
     let response = self.brain.nn.propagate(vision);
 
     // jump
-    if response[0] > 0.5 && self.jump_timer > 19 {
-      self.dy += 0.019;
+    if response[0] > 0.5 && self.jump_timer > self.jump_cooldown {
+      self.dy += config.flap_impulse;
       self.jump_timer = 0;
+      self.flaps += 1;
     }
 
     self.jump_timer += 1;
   }
 
-  pub(crate) fn movement(&mut self) { 
+  pub(crate) fn movement(&mut self, config: &SimulationConfig) { 
     // gravity
-    self.dy += -0.0006;
-    self.dy = self.dy.max(-0.014).min(0.015);
+    self.dy += config.gravity;
+    self.dy = self.dy.max(config.min_velocity).min(config.max_velocity);
 
     // add
     self.position.y += self.dy;
-    self.fit_distance = 20.0*self.passes as f64 + 0.1;
+    self.distance += config.pipe_dx;
+    self.ticks += 1;
+    self.grace = self.grace.saturating_sub(1);
+  }
+
+  pub(crate) fn pass(&mut self, ticks: usize) {
+    self.pass_ticks.push(ticks);
+  }
+
+  pub(crate) fn die(&mut self, gap: na::Point2<f64>) {
+    self.gap_distance = (self.position.y - gap.y).abs();
+    self.alive = false;
+  }
+
+  pub(crate) fn score(&mut self, fitness: &dyn FitnessFunction) {
+    self.fit_distance = fitness.fitness(&self.episode());
   }
 
   fn collision_screen(&self) -> bool {
@@ -74,9 +122,9 @@ impl Bird {
     !(BIRD_OFFSET_Y..=1.0 - BIRD_OFFSET_Y).contains(&y)
   }
 
-  fn collision_pipe(&self, closest_pipe: Option<&Pipe>) -> bool {    
+  fn collision_pipe(&self, closest_pipe: Option<&Pipe>, config: &SimulationConfig) -> bool {    
     if let Some(pipe) = closest_pipe {
-      self.collision_aabb(pipe.top_rectangle()) || self.collision_aabb(pipe.bot_rectangle())
+      self.collision_aabb(pipe.top_rectangle(config)) || self.collision_aabb(pipe.bot_rectangle(config))
     } else {
       false
     }
@@ -99,6 +147,48 @@ impl Bird {
   pub fn fit_distance(&self) -> f64 {
     self.fit_distance
   }
+
+  pub fn distance(&self) -> f64 {
+    self.distance
+  }
+
+  pub fn passes(&self) -> usize {
+    self.pass_ticks.len()
+  }
+
+  pub fn pass_ticks(&self) -> &[usize] {
+    &self.pass_ticks
+  }
+
+  pub fn gap_distance(&self) -> f64 {
+    self.gap_distance
+  }
+
+  pub fn episode(&self) -> Episode {
+    Episode {
+      ticks: self.ticks,
+      passes: self.passes(),
+      flaps: self.flaps,
+      distance: self.distance,
+      gap_distance: (!self.alive).then_some(self.gap_distance),
+    }
+  }
+
+  pub fn flaps(&self) -> usize {
+    self.flaps
+  }
+
+  pub fn traits(&self) -> BirdTraits {
+    BirdTraits {
+      jump_cooldown: self.jump_cooldown,
+      eye: self.eye.inputs(),
+    }
+  }
+
+  // look this up in `Simulation::lineage`
+  pub fn id(&self) -> u64 {
+    self.id
+  }
 }
 
 impl AABB for Bird {
@@ -2,22 +2,71 @@ use rand::RngCore;
 
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(crate) struct BirdIndividual {
   pub(crate) fitness: f64,
   pub(crate) chromosome: ga::Chromosome,
+  pub(crate) behaviour: Vec<f64>, // distance travelled and height at death
+  pub(crate) objectives: Vec<f64>, // pipes passed, distance per flap, negated brain complexity
+  pub(crate) age: usize,
+  pub(crate) id: u64,
+  pub(crate) traits: Option<BirdTraits>, // none for offspring whose traits do not evolve
+  pub(crate) violation: f64, // of the bred weights, before they were bounded
 }
 
 impl BirdIndividual {
-  pub(crate) fn into_bird(self, rng: &mut impl RngCore) -> Bird {
-    Bird::from_chromosome(self.chromosome, rng)
+  pub(crate) fn into_bird(mut self, rng: &mut impl RngCore, config: &SimulationConfig) -> Bird {
+    let bounds: ga::GeneBounds = config.weight_bounds.into();
+    let violation = bounds.violation(&self.chromosome);
+    self.chromosome.bound(rng, &bounds);
+
+    let mut bird = Bird::from_chromosome(self.chromosome, rng, config);
+    bird.id = self.id;
+    bird.violation = violation;
+    if let Some(traits) = &self.traits {
+      bird.set_traits(traits);
+    }
+    bird
   }
 }
 
 impl ga::Individual for BirdIndividual {
+  type Gene = f64;
+
   fn fitness(&self) -> f64 {
     self.fitness
   }
+
+  fn behaviour(&self) -> Option<Vec<f64>> {
+    Some(self.behaviour.clone())
+  }
+
+  // how far the weights were bred outside of the configured `weight_bounds`
+  // before being brought back within them, penalized only when the optimizer
+  // has a `Penalty`
+  fn violation(&self) -> f64 {
+    self.violation
+  }
+
+  fn objectives(&self) -> Option<Vec<f64>> {
+    Some(self.objectives.clone())
+  }
+}
+
+impl ga::AgedIndividual for BirdIndividual {
+  fn age(&self) -> usize {
+    self.age
+  }
+}
+
+impl ga::TrackedIndividual for BirdIndividual {
+  fn id(&self) -> u64 {
+    self.id
+  }
+
+  fn set_id(&mut self, id: u64) {
+    self.id = id;
+  }
 }
 
 impl AsRef<ga::Chromosome> for BirdIndividual {
@@ -30,7 +79,13 @@ impl From<ga::Chromosome> for BirdIndividual {
   fn from(chromosome: ga::Chromosome) -> Self {
     Self {
       fitness: 0.0,
-      chromosome
+      chromosome,
+      behaviour: vec![0.0, 0.0],
+      objectives: vec![0.0, 0.0, 0.0],
+      age: 0,
+      id: 0,
+      traits: None,
+      violation: 0.0,
     }
   }
 }
@@ -40,6 +95,52 @@ impl From<Bird> for BirdIndividual {
     Self {
       fitness: bird.fit_distance,
       chromosome: bird.brain.chromosome(),
+      behaviour: vec![bird.distance, bird.position.y],
+      objectives: vec![
+        bird.passes() as f64,
+        bird.distance / (bird.flaps + 1) as f64,
+        -bird.brain.complexity(),
+      ],
+      age: bird.age,
+      id: bird.id,
+      traits: Some(bird.traits()),
+      violation: bird.violation,
     }
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn brains_are_bred_within_the_weight_bounds() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let config = SimulationConfig::default();
+
+    let len = Brain::random(&mut rng).chromosome().len();
+    let chromosome: ga::Chromosome = (0..len).map(|i| if i % 2 == 0 { 5.0 } else { -0.5 }).collect();
+    let bird = BirdIndividual::from(chromosome).into_bird(&mut rng, &config);
+
+    let weights = bird.brain.chromosome();
+    assert!(weights.iter().step_by(2).all(|&weight| weight == WEIGHT_LIMIT));
+    assert!(weights.iter().skip(1).step_by(2).all(|&weight| weight == -0.5));
+  }
+
+  #[test]
+  fn violation_is_measured_against_the_configured_bounds() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let mut config = SimulationConfig::default();
+    config.weight_bounds.min = -1.0;
+    config.weight_bounds.max = 1.0;
+
+    let len = Brain::random(&mut rng).chromosome().len();
+    let chromosome: ga::Chromosome = (0..len).map(|i| if i == 0 { 1.5 } else { 0.0 }).collect();
+    let bird = BirdIndividual::from(chromosome).into_bird(&mut rng, &config);
+
+    // 1.5 is within `WEIGHT_LIMIT`, but half a unit beyond the configured bounds
+    let individual = BirdIndividual::from(bird);
+    assert_eq!(ga::Individual::violation(&individual), 0.5);
+    assert_eq!(individual.chromosome[0], 1.0);
+  }
+}
@@ -0,0 +1,174 @@
+use serde::{Serialize, Deserialize};
+
+use crate::*;
+
+pub const JUMP_COOLDOWN_MIN: usize = 5;
+pub const JUMP_COOLDOWN_MAX: usize = 40;
+
+pub type TraitsGeneticAlgorithm = ga::GeneticAlgorithm<
+  ga::RouletteWheelSelection,
+  ga::UniformCrossover,
+  ga::MixedMutation,
+>;
+
+// discrete parameters of a bird, evolved next to the brain by their own
+// operators, but inherited from the same parents as its weights
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BirdTraits {
+  pub jump_cooldown: usize, // ticks between flaps
+  pub eye: [bool; EYE_INPUTS], // which vision inputs reach the brain
+}
+
+impl BirdTraits {
+  pub fn chromosome(&self) -> ga::Chromosome<ga::MixedGene> {
+    let cooldown = ga::MixedGene::Integer {
+      value: self.jump_cooldown as i64,
+      min: JUMP_COOLDOWN_MIN as i64,
+      max: JUMP_COOLDOWN_MAX as i64,
+    };
+
+    std::iter::once(cooldown)
+      .chain(self.eye.iter().map(|&input| ga::MixedGene::Bit(input)))
+      .collect()
+  }
+
+  pub(crate) fn genetic_algorithm() -> TraitsGeneticAlgorithm {
+    ga::GeneticAlgorithm::new(
+      ga::RouletteWheelSelection::new(),
+      ga::UniformCrossover::new(),
+      ga::MixedMutation::new(0.05, 0.0, 2),
+    )
+  }
+}
+
+impl Default for BirdTraits {
+  fn default() -> Self {
+    Self {
+      jump_cooldown: SimulationConfig::default().jump_cooldown,
+      eye: [true; EYE_INPUTS],
+    }
+  }
+}
+
+impl From<ga::Chromosome<ga::MixedGene>> for BirdTraits {
+  fn from(chromosome: ga::Chromosome<ga::MixedGene>) -> Self {
+    assert_eq!(chromosome.len(), 1 + EYE_INPUTS);
+
+    let mut genes = chromosome.into_iter();
+
+    let jump_cooldown = match genes.next() {
+      Some(ga::MixedGene::Integer { value, .. }) => value as usize,
+      gene => panic!("Error - expected jump cooldown, got {:?}", gene),
+    };
+
+    let mut eye = [true; EYE_INPUTS];
+    for (input, gene) in eye.iter_mut().zip(genes) {
+      *input = match gene {
+        ga::MixedGene::Bit(bit) => bit,
+        gene => panic!("Error - expected eye input, got {:?}", gene),
+      };
+    }
+
+    Self { jump_cooldown, eye }
+  }
+}
+
+// traits of a bird as the traits' genetic algorithm sees them
+pub(crate) struct TraitsIndividual {
+  fitness: f64,
+  chromosome: ga::Chromosome<ga::MixedGene>,
+}
+
+impl ga::Individual for TraitsIndividual {
+  type Gene = ga::MixedGene;
+
+  fn fitness(&self) -> f64 {
+    self.fitness
+  }
+}
+
+impl AsRef<ga::Chromosome<ga::MixedGene>> for TraitsIndividual {
+  fn as_ref(&self) -> &ga::Chromosome<ga::MixedGene> {
+    &self.chromosome
+  }
+}
+
+impl From<ga::Chromosome<ga::MixedGene>> for TraitsIndividual {
+  fn from(chromosome: ga::Chromosome<ga::MixedGene>) -> Self {
+    Self {
+      fitness: 0.0,
+      chromosome,
+    }
+  }
+}
+
+// hands every child bred from two birds a cross of their traits, and children
+// without parents (from optimizers that do not breed) the traits of a child of
+// two birds picked by the traits' genetic algorithm
+pub(crate) struct TraitsBreeder<'a> {
+  ga: &'a TraitsGeneticAlgorithm,
+  population: Vec<TraitsIndividual>,
+}
+
+impl<'a> TraitsBreeder<'a> {
+  pub(crate) fn new(ga: &'a TraitsGeneticAlgorithm, evaluated: &[BirdIndividual]) -> Self {
+    let population = evaluated
+      .iter()
+      .map(|individual| TraitsIndividual {
+        fitness: individual.fitness,
+        chromosome: individual.traits.clone().unwrap_or_default().chromosome(),
+      })
+      .collect();
+
+    Self { ga, population }
+  }
+}
+
+impl ga::BreedHook<BirdIndividual> for TraitsBreeder<'_> {
+  fn bred(&mut self, mut rng: &mut dyn RngCore, [parent_a, parent_b]: [&BirdIndividual; 2], _crossed: &ga::Chromosome, child: &mut BirdIndividual) {
+    let parent_a = parent_a.traits.clone().unwrap_or_default().chromosome();
+    let parent_b = parent_b.traits.clone().unwrap_or_default().chromosome();
+
+    child.traits = Some(self.ga.recombine(&mut rng, &parent_a, &parent_b).into());
+  }
+
+  fn founded(&mut self, mut rng: &mut dyn RngCore, child: &mut BirdIndividual) {
+    let [parent_a, parent_b] = self.ga.parents(&mut rng, &self.population);
+    let traits = self.ga.recombine(&mut rng, parent_a.as_ref(), parent_b.as_ref());
+
+    child.traits = Some(traits.into());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ga::BreedHook;
+
+  fn individual(jump_cooldown: usize) -> BirdIndividual {
+    let mut individual = BirdIndividual::from(Brain::random(&mut ChaCha8Rng::seed_from_u64(7)).chromosome());
+    individual.traits = Some(BirdTraits {
+      jump_cooldown,
+      eye: [true; EYE_INPUTS],
+    });
+    individual
+  }
+
+  #[test]
+  fn children_inherit_the_traits_of_their_parents() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let ga = ga::GeneticAlgorithm::new(
+      ga::RouletteWheelSelection::new(),
+      ga::UniformCrossover::new(),
+      ga::MixedMutation::new(0.0, 0.0, 1),
+    );
+    let population = [individual(10), individual(20), individual(30)];
+    let mut breeder = TraitsBreeder::new(&ga, &population);
+
+    let mut child = individual(0);
+    let crossed = child.chromosome.clone();
+    breeder.bred(&mut rng, [&population[2], &population[2]], &crossed, &mut child);
+
+    assert_eq!(child.traits.unwrap().jump_cooldown, 30);
+  }
+}
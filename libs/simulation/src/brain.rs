@@ -2,20 +2,45 @@ use rand::RngCore;
 
 use crate::*;
 
+// evolved weights kept within this range quantize to 8 bits with little loss
+pub const WEIGHT_LIMIT: f64 = 2.0;
+
 #[derive(Debug, Clone)]
 pub struct Brain {
   pub(crate) nn: nn::Network,
+  step_sizes: Vec<f64>, // carried for `SelfAdaptiveMutation`, empty otherwise
 }
 
 impl Brain {
   pub(crate) fn random(rng: &mut impl RngCore) -> Self {
     Self { 
       nn: nn::Network::random(rng, &Self::topology()), 
+      step_sizes: Vec::new(),
     }
   }
 
   pub(crate) fn chromosome(&self) -> ga::Chromosome {
-    self.nn.weights().collect()
+    let chromosome: ga::Chromosome = self.nn.weights().collect();
+    if self.step_sizes.is_empty() {
+      chromosome
+    } else {
+      chromosome.with_step_sizes(self.step_sizes.clone())
+    }
+  }
+
+  // hand to `GeneticAlgorithmBuilder::bounds` to keep weights within `WEIGHT_LIMIT`
+  pub fn weight_bounds() -> ga::GeneBounds {
+    ga::GeneBounds::uniform(-WEIGHT_LIMIT, WEIGHT_LIMIT, ga::BoundaryHandling::Clamp)
+  }
+
+  // mean magnitude of the weights, smooth where a count of non-zero weights
+  // would jump at whatever threshold it counted from
+  pub(crate) fn complexity(&self) -> f64 {
+    let (sum, count) = self.nn
+      .weights()
+      .fold((0.0, 0), |(sum, count), weight| (sum + weight.abs(), count + 1));
+
+    sum / count as f64
   }
 
   fn topology() -> [nn::LayerArchitecture; 4] {
@@ -25,8 +50,35 @@ impl Brain {
 
 impl From<ga::Chromosome> for Brain {
   fn from(chromosome: ga::Chromosome) -> Self {
+    let step_sizes = chromosome.step_sizes().to_vec();
     Self { 
       nn: nn::Network::from_weights(&Self::topology(), chromosome), 
+      step_sizes,
     }
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn step_sizes_survive_the_network() {
+    let weights = Brain::random(&mut ChaCha8Rng::seed_from_u64(7)).chromosome();
+    let step_sizes: Vec<f64> = (0..weights.len()).map(|i| i as f64 * 0.01).collect();
+    let chromosome = weights.clone().with_step_sizes(step_sizes.clone());
+
+    let brain = Brain::from(chromosome);
+
+    assert!(brain.chromosome().iter().eq(weights.iter()));
+    assert_eq!(brain.chromosome().step_sizes(), &step_sizes[..]);
+  }
+
+  #[test]
+  fn complexity_is_the_mean_weight_magnitude() {
+    let len = Brain::random(&mut ChaCha8Rng::seed_from_u64(7)).chromosome().len();
+    let chromosome: ga::Chromosome = (0..len).map(|i| if i % 2 == 0 { 0.5 } else { -0.5 }).collect();
+
+    assert!((Brain::from(chromosome).complexity() - 0.5).abs() < 1e-12);
+  }
+}
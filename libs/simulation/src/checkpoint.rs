@@ -0,0 +1,193 @@
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+
+use std::io;
+use std::path::Path;
+
+use crate::*;
+
+pub type SimulationCheckpoint = ga::Checkpoint<ChaCha8Rng, WorldState>;
+
+// what the genetic algorithm does not know about: the episode in flight, and
+// whatever state the optimizer carries between generations
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorldState {
+  tick_count: usize,
+  birds: Vec<BirdState>, // in population order, like the chromosomes
+  pipes: Vec<[f64; 2]>,
+  #[serde(default)]
+  course: Vec<f64>,
+  #[serde(default)]
+  pipes_spawned: usize,
+  #[serde(default)]
+  optimizer: serde_json::Value,
+  lineage: Option<ga::Lineage>,
+  evolving_traits: bool,
+  #[serde(default)]
+  config: SimulationConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BirdState {
+  alive: bool,
+  position: [f64; 2],
+  dy: f64,
+  #[serde(default)]
+  pass_ticks: Vec<usize>,
+  distance: f64,
+  #[serde(default)]
+  gap_distance: f64,
+  flaps: usize,
+  #[serde(default)]
+  ticks: usize,
+  jump_timer: usize,
+  #[serde(default)]
+  grace: usize,
+  #[serde(default)]
+  violation: f64,
+  age: usize,
+  id: u64,
+  traits: BirdTraits,
+}
+
+impl BirdState {
+  fn new(bird: &Bird, alive: bool) -> Self {
+    Self {
+      alive,
+      position: [bird.position.x, bird.position.y],
+      dy: bird.dy,
+      pass_ticks: bird.pass_ticks.clone(),
+      distance: bird.distance,
+      gap_distance: bird.gap_distance,
+      flaps: bird.flaps,
+      ticks: bird.ticks,
+      jump_timer: bird.jump_timer,
+      grace: bird.grace,
+      violation: bird.violation,
+      age: bird.age,
+      id: bird.id,
+      traits: bird.traits(),
+    }
+  }
+
+  fn into_bird(self, chromosome: ga::Chromosome, fitness: f64, index: usize) -> Bird {
+    Bird {
+      position: na::Point2::new(self.position[0], self.position[1]),
+      dy: self.dy,
+      fit_distance: fitness,
+      pass_ticks: self.pass_ticks,
+      distance: self.distance,
+      gap_distance: self.gap_distance,
+      flaps: self.flaps,
+      ticks: self.ticks,
+      jump_timer: self.jump_timer,
+      jump_cooldown: self.traits.jump_cooldown,
+      index,
+      age: self.age,
+      id: self.id,
+      grace: self.grace,
+      violation: self.violation,
+      alive: self.alive,
+      eye: Eye::new(self.traits.eye),
+      brain: chromosome.into(),
+    }
+  }
+}
+
+impl<O> Simulation<O>
+  where O: ga::Optimizer, {
+
+  // the optimizer's state is saved along, see `ga::Optimizer::save_state`
+  pub fn save_checkpoint(&self, path: impl AsRef<Path>, rng: &ChaCha8Rng) -> io::Result<()> {
+    let mut birds: Vec<_> = self.world.alive_birds
+      .iter()
+      .map(|bird| (bird, true))
+      .chain(self.world.dead_birds.iter().map(|bird| (bird, false)))
+      .collect();
+    birds.sort_by_key(|(bird, _)| bird.index);
+
+    let population: Vec<BirdIndividual> = birds
+      .iter()
+      .map(|(bird, _)| (*bird).clone().into())
+      .collect();
+
+    let state = WorldState {
+      tick_count: self.tick_count,
+      birds: birds
+        .iter()
+        .map(|(bird, alive)| BirdState::new(bird, *alive))
+        .collect(),
+      pipes: self.world.pipes
+        .iter()
+        .map(|pipe| [pipe.position.x, pipe.position.y])
+        .collect(),
+      course: self.world.course.clone(),
+      pipes_spawned: self.world.pipes_spawned,
+      optimizer: self.optimizer.save_state(),
+      lineage: self.lineage.clone(),
+      evolving_traits: self.traits_optimizer.is_some(),
+      config: self.world.config.clone(),
+    };
+
+    SimulationCheckpoint::new(self.generations, &population, &self.history, rng.clone(), state).save(path)
+  }
+
+  // resumes exactly where `save_checkpoint` left off when stepped with the
+  // returned rng, given an optimizer configured like the saved one
+  pub fn load_checkpoint(path: impl AsRef<Path>, mut optimizer: O) -> io::Result<(Self, ChaCha8Rng)> {
+    let checkpoint = SimulationCheckpoint::load(path)?;
+
+    if checkpoint.extra.birds.len() != checkpoint.chromosomes.len() {
+      return Err(io::Error::new(io::ErrorKind::InvalidData, "checkpoint birds do not match its population"));
+    }
+    checkpoint.extra.config.validate()?;
+
+    let mut world = World {
+      alive_birds: Vec::new(),
+      dead_birds: Vec::new(),
+      pipes: checkpoint.extra.pipes
+        .iter()
+        .map(|&[x, y]| Pipe { position: na::Point2::new(x, y) })
+        .collect(),
+      course: checkpoint.extra.course,
+      pipes_spawned: checkpoint.extra.pipes_spawned,
+      fitness: checkpoint.extra.config.fitness.build().into(),
+      config: checkpoint.extra.config,
+    };
+
+    let birds = checkpoint.extra.birds
+      .into_iter()
+      .zip(checkpoint.chromosomes)
+      .zip(checkpoint.fitnesses)
+      .enumerate();
+
+    for (index, ((state, chromosome), fitness)) in birds {
+      let alive = state.alive;
+      let bird = state.into_bird(chromosome, fitness, index);
+
+      if alive {
+        world.alive_birds.push(bird);
+      } else {
+        world.dead_birds.push(bird);
+      }
+    }
+
+    optimizer.load_state(checkpoint.extra.optimizer)?;
+    optimizer.adapt(&checkpoint.history);
+
+    let simulation = Self {
+      world,
+      tick_count: checkpoint.extra.tick_count,
+      generations: checkpoint.generation,
+      optimizer,
+      history: checkpoint.history,
+      pareto_front: Vec::new(), // refilled by the next evolved generation
+      lineage: checkpoint.extra.lineage,
+      traits_optimizer: checkpoint.extra.evolving_traits.then(BirdTraits::genetic_algorithm),
+      observer: Box::new(()),
+      stopped: false,
+    };
+
+    Ok((simulation, checkpoint.rng))
+  }
+}
@@ -0,0 +1,283 @@
+use rand::Rng;
+
+use crate::*;
+
+use std::mem;
+
+pub const DESIGNER_COUNT: usize = 7;
+pub const COURSE_LENGTH: usize = 8; // gaps a designer lays out before its course repeats
+
+pub type DesignerGeneticAlgorithm = ga::GeneticAlgorithm<
+  ga::RouletteWheelSelection,
+  ga::UniformCrossover,
+  ga::BoundedMutation<ga::GaussianMutation>,
+>;
+
+pub type CoevolutionGeneticAlgorithm = ga::Coevolution<ga::DefaultGeneticAlgorithm, DesignerGeneticAlgorithm>;
+
+// adversary of the birds, its genes are the gap heights of its course
+#[derive(Clone, Debug)]
+pub(crate) struct DesignerIndividual {
+  fitness: f64,
+  chromosome: ga::Chromosome,
+}
+
+impl ga::Individual for DesignerIndividual {
+  type Gene = f64;
+
+  fn fitness(&self) -> f64 {
+    self.fitness
+  }
+}
+
+impl AsRef<ga::Chromosome> for DesignerIndividual {
+  fn as_ref(&self) -> &ga::Chromosome {
+    &self.chromosome
+  }
+}
+
+impl From<ga::Chromosome> for DesignerIndividual {
+  fn from(chromosome: ga::Chromosome) -> Self {
+    Self {
+      fitness: 0.0,
+      chromosome,
+    }
+  }
+}
+
+// birds coevolved against pipe designers: every designer lays out the pipes of
+// its own world and scores by how few pipes the birds flying it pass, as long
+// as its best bird shows the course can be passed, while every bird scores its
+// mean fitness over the designers it met
+pub struct CoevolutionSimulation {
+  worlds: Vec<World>, // one per designer
+  encounters: Vec<Vec<usize>>, // population indices of the birds flying every world
+  designers: Vec<DesignerIndividual>,
+  population: usize,
+  tick_count: usize,
+  generations: usize,
+  coevolution: CoevolutionGeneticAlgorithm,
+  history: Vec<(ga::Statistics, ga::Statistics)>, // birds, designers
+}
+
+impl CoevolutionSimulation {
+  pub fn random(rng: &mut impl RngCore, samples: usize) -> Self {
+    let designer_alg = ga::GeneticAlgorithm::new(
+      ga::RouletteWheelSelection::new(),
+      ga::UniformCrossover::new(),
+      ga::BoundedMutation::new(
+        ga::GaussianMutation::new(0.1, 0.2),
+        ga::GeneBounds::uniform(0.0, 1.0, ga::BoundaryHandling::Reflect),
+      ),
+    );
+
+    Self::new(rng, ga::Coevolution::new(ga::DefaultGeneticAlgorithm::default(), designer_alg, samples))
+  }
+
+  pub fn new(rng: &mut impl RngCore, coevolution: CoevolutionGeneticAlgorithm) -> Self {
+    let birds: Vec<_> = World::random(rng).birds_as_individuals().collect();
+    let designers = (0..DESIGNER_COUNT)
+      .map(|_| {
+        (0..COURSE_LENGTH)
+          .map(|_| rng.gen())
+          .collect::<ga::Chromosome>()
+          .into()
+      })
+      .collect();
+
+    let mut simulation = Self {
+      worlds: Vec::new(),
+      encounters: Vec::new(),
+      designers,
+      population: birds.len(),
+      tick_count: 0,
+      generations: 0,
+      coevolution,
+      history: Vec::new(),
+    };
+    simulation.deal(rng, birds);
+
+    simulation
+  }
+
+  pub fn step(&mut self, rng: &mut impl RngCore) -> bool {
+    let ticks = self.tick_count;
+    self.tick_count += 1;
+
+    for world in &mut self.worlds {
+      if !world.alive_birds.is_empty() {
+        world.step(ticks, rng);
+      }
+    }
+
+    // a course repeats, so birds that mastered it would never come down
+    if self.tick_count >= EPISODE_TICK_LIMIT {
+      for world in &mut self.worlds {
+        let survivors = mem::take(&mut world.alive_birds);
+        world.dead_birds.extend(survivors);
+      }
+    }
+
+    if self.worlds.iter().all(|world| world.alive_birds.is_empty()) {
+      self.evolve(rng);
+
+      self.tick_count = 0;
+      self.generations += 1;
+
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn train(&mut self, rng: &mut impl RngCore) {
+    let new_tick_count = self.tick_count + 10000;
+    loop {
+      if self.step(rng) || self.tick_count >= new_tick_count {
+        return;
+      }
+    }
+  }
+
+  fn evolve(&mut self, rng: &mut impl RngCore) {
+    // score designers, and gather every flight of every bird
+    let mut flights = vec![Vec::new(); self.population];
+
+    for ((world, met), designer) in self.worlds.iter_mut().zip(&self.encounters).zip(&mut self.designers) {
+      let passes: Vec<_> = world.dead_birds
+        .iter()
+        .map(Bird::passes)
+        .collect();
+      designer.fitness = designer_fitness(&passes);
+
+      for (&bird, individual) in met.iter().zip(world.birds_as_individuals()) {
+        flights[bird].push(individual);
+      }
+    }
+
+    // score birds
+    let birds: Vec<_> = flights
+      .into_iter()
+      .map(|flights| {
+        let fitness = flights.iter().map(|bird| bird.fitness).sum::<f64>() / flights.len() as f64;
+
+        let mut bird = flights.into_iter().next().expect("Error - bird met no designer");
+        bird.fitness = fitness;
+        bird
+      })
+      .collect();
+
+    // evolve both populations
+    let ((birds, bird_stats), (designers, designer_stats)) = self.coevolution.next_generation(rng, &birds, &self.designers);
+
+    // record generation
+    self.history.push((bird_stats, designer_stats));
+
+    // bring back populations
+    self.designers = designers;
+    self.deal(rng, birds);
+  }
+
+  // hand the birds out to the worlds of the designers they meet
+  fn deal(&mut self, rng: &mut impl RngCore, birds: Vec<BirdIndividual>) {
+    self.encounters = self.coevolution.encounters(rng, birds.len(), self.designers.len());
+
+    self.worlds = self.designers
+      .iter()
+      .zip(&self.encounters)
+      .map(|(designer, met)| {
+        let mut world = World::with_course(designer.chromosome.iter().copied().collect(), SimulationConfig::default());
+        let birds = met.iter().map(|&bird| birds[bird].clone());
+
+        world.alive_birds = world.individuals_as_birds(birds, rng).collect();
+        world
+      })
+      .collect();
+  }
+}
+
+// difficulty of a course, the fewer pipes its birds pass on average the
+// harder, times its passability, the share of a whole course its best bird
+// passed; a course none of its birds gets through scores nothing, however
+// hard it is
+pub(crate) fn designer_fitness(passes: &[usize]) -> f64 {
+  let best = passes.iter().copied().max().unwrap_or(0);
+  let mean = passes.iter().sum::<usize>() as f64 / passes.len().max(1) as f64;
+
+  let passability = best.min(COURSE_LENGTH) as f64 / COURSE_LENGTH as f64;
+  let difficulty = 1.0 / (1.0 + mean);
+
+  passability * difficulty
+}
+
+impl CoevolutionSimulation {
+  pub fn worlds(&self) -> &[World] {
+    &self.worlds
+  }
+
+  pub fn tick_count(&self) -> usize {
+    self.tick_count
+  }
+
+  pub fn generations(&self) -> usize {
+    self.generations
+  }
+
+  // statistics of the birds and of the designers for every evolved generation, oldest first
+  pub fn history(&self) -> &[(ga::Statistics, ga::Statistics)] {
+    &self.history
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn impossible_courses_score_nothing() {
+    assert_eq!(designer_fitness(&[0, 0, 0]), 0.0);
+    assert_eq!(designer_fitness(&[]), 0.0);
+  }
+
+  #[test]
+  fn hard_but_passable_courses_score_best() {
+    let easy = designer_fitness(&[COURSE_LENGTH; 4]);
+    let hard = designer_fitness(&[COURSE_LENGTH, 0, 0, 0]);
+    let barely = designer_fitness(&[1, 0, 0, 0]);
+
+    assert!(hard > easy);
+    assert!(hard > barely);
+    assert!(barely > designer_fitness(&[0; 4]));
+
+    // passing more than a whole course proves nothing more
+    assert_eq!(designer_fitness(&[2 * COURSE_LENGTH]), 1.0 / (1.0 + 2.0 * COURSE_LENGTH as f64));
+  }
+
+  #[test]
+  fn every_bird_flies_the_courses_of_the_designers_it_meets() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let mut simulation = CoevolutionSimulation::random(&mut rng, 2);
+    let population = simulation.population;
+
+    // one world per designer, laid out as its genes
+    assert_eq!(simulation.worlds().len(), DESIGNER_COUNT);
+    for (world, designer) in simulation.worlds().iter().zip(&simulation.designers) {
+      assert!(world.course.iter().eq(designer.chromosome.iter()));
+    }
+
+    let flights: usize = simulation.worlds().iter().map(|world| world.alive_birds.len()).sum();
+    assert_eq!(flights, 2 * population);
+
+    while !simulation.step(&mut rng) {}
+
+    assert_eq!(simulation.generations(), 1);
+    assert_eq!(simulation.history().len(), 1);
+    assert_eq!(simulation.designers.len(), DESIGNER_COUNT);
+
+    // the designers bred for the next generation lay out the new worlds
+    for (world, designer) in simulation.worlds().iter().zip(&simulation.designers) {
+      assert!(world.course.iter().eq(designer.chromosome.iter()));
+      assert!(designer.chromosome.iter().all(|gap| (0.0..=1.0).contains(gap)));
+    }
+  }
+}
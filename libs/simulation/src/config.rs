@@ -0,0 +1,152 @@
+use serde::{Serialize, Deserialize};
+
+use crate::*;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// physics and layout of the world, every field optional in a config file
+// and defaulting to the values the simulation was tuned with
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+  pub gravity: f64, // added to a bird's velocity every tick
+  pub flap_impulse: f64, // added to a bird's velocity on every flap
+  pub min_velocity: f64,
+  pub max_velocity: f64,
+  pub jump_cooldown: usize, // ticks between flaps, unless bird traits evolve
+  pub bird_x: f64,
+  pub pipe_dx: f64, // distance pipes move towards the birds every tick
+  pub pipe_offset_x: f64, // pipe/gap width = 2*pipe_offset_x
+  pub pipe_offset_y: f64, // pipe gap height = 2*pipe_offset_y
+  pub pipe_tick_generation: usize, // ticks between two pipes
+  pub population_count: usize,
+  pub fitness: FitnessConfig,
+  pub weight_bounds: ga::BoundsConfig, // every bred brain is brought back within these
+}
+
+impl SimulationConfig {
+  // toml, or json when the file ends in .json
+  pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    match path.extension().and_then(|extension| extension.to_str()) {
+      Some("json") => Self::from_json(&contents),
+      _ => Self::from_toml(&contents),
+    }
+  }
+
+  pub fn from_toml(contents: &str) -> io::Result<Self> {
+    let config: Self = toml::from_str(contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    config.validate()?;
+    Ok(config)
+  }
+
+  pub fn from_json(contents: &str) -> io::Result<Self> {
+    let config: Self = serde_json::from_str(contents).map_err(io::Error::from)?;
+    config.validate()?;
+    Ok(config)
+  }
+
+  // settings that would panic or leave a world nothing could ever fly through
+  pub fn validate(&self) -> io::Result<()> {
+    let numbers = [
+      self.gravity,
+      self.flap_impulse,
+      self.min_velocity,
+      self.max_velocity,
+      self.bird_x,
+      self.pipe_dx,
+      self.pipe_offset_x,
+      self.pipe_offset_y,
+    ];
+
+    check(numbers.iter().all(|number| number.is_finite()), "physics and layout must be finite numbers")?;
+    check(self.min_velocity <= self.max_velocity, "min_velocity must not exceed max_velocity")?;
+    check((0.0..=1.0).contains(&self.bird_x), "bird_x must be on screen, within 0..=1")?;
+    check(self.pipe_dx > 0.0, "pipe_dx must be positive")?;
+    check(self.pipe_offset_x > 0.0, "pipe_offset_x must be positive")?;
+    check(self.pipe_offset_y > 0.0 && self.pipe_offset_y < 0.5, "pipe_offset_y must be within 0..0.5, for the gap to fit on screen")?;
+    check(self.pipe_tick_generation > 0, "pipe_tick_generation must be positive")?;
+    check(self.population_count > 0, "population_count must be positive")?;
+
+    if let FitnessConfig::FlapEconomy { flap_cost } = self.fitness {
+      check(flap_cost.is_finite() && flap_cost >= 0.0, "flap_cost must not be negative")?;
+    }
+
+    let ga::BoundsConfig { min, max, .. } = self.weight_bounds;
+    check(min.is_finite() && max.is_finite() && min <= max, "weight_bounds must be a finite, non-empty range")
+  }
+}
+
+fn check(valid: bool, message: &str) -> io::Result<()> {
+  if valid {
+    Ok(())
+  } else {
+    Err(io::Error::new(io::ErrorKind::InvalidInput, message.to_string()))
+  }
+}
+
+impl Default for SimulationConfig {
+  fn default() -> Self {
+    Self {
+      gravity: -0.0006,
+      flap_impulse: 0.019,
+      min_velocity: -0.014,
+      max_velocity: 0.015,
+      jump_cooldown: 19,
+      bird_x: 0.15,
+      pipe_dx: 0.0045,
+      pipe_offset_x: 0.044,
+      pipe_offset_y: 0.13,
+      pipe_tick_generation: 120,
+      population_count: 70,
+      fitness: FitnessConfig::default(),
+      weight_bounds: ga::BoundsConfig {
+        min: -WEIGHT_LIMIT,
+        max: WEIGHT_LIMIT,
+        handling: ga::BoundaryHandling::Clamp,
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn invalid(config: SimulationConfig) -> bool {
+    config.validate().is_err_and(|err| err.kind() == io::ErrorKind::InvalidInput)
+  }
+
+  #[test]
+  fn default_is_valid() {
+    assert!(SimulationConfig::default().validate().is_ok());
+  }
+
+  #[test]
+  fn rejects_what_could_not_be_simulated() {
+    let default = SimulationConfig::default;
+
+    assert!(invalid(SimulationConfig { pipe_tick_generation: 0, ..default() }));
+    assert!(invalid(SimulationConfig { population_count: 0, ..default() }));
+    assert!(invalid(SimulationConfig { min_velocity: 0.1, max_velocity: -0.1, ..default() }));
+    assert!(invalid(SimulationConfig { pipe_offset_y: 0.5, ..default() }));
+    assert!(invalid(SimulationConfig { pipe_dx: 0.0, ..default() }));
+    assert!(invalid(SimulationConfig { gravity: f64::NAN, ..default() }));
+    assert!(invalid(SimulationConfig { fitness: FitnessConfig::FlapEconomy { flap_cost: -1.0 }, ..default() }));
+  }
+
+  #[test]
+  fn files_are_validated() {
+    let err = SimulationConfig::from_toml("population_count = 0").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let err = SimulationConfig::from_json(r#"{ "pipe_offset_y": 0.7 }"#).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    assert!(SimulationConfig::from_toml("population_count = 10").is_ok());
+  }
+}
@@ -2,22 +2,36 @@ use nalgebra as na;
 
 use crate::*;
 
+pub const EYE_INPUTS: usize = 5;
+
 #[derive(Debug, Clone)]
-pub(crate) struct Eye;
+pub(crate) struct Eye {
+  inputs: [bool; EYE_INPUTS], // disabled inputs always read 0
+}
 
 impl Eye {
-  pub(crate) fn new() -> Self {
-    Self
+  pub(crate) fn new(inputs: [bool; EYE_INPUTS]) -> Self {
+    Self { inputs }
+  }
+
+  pub(crate) fn inputs(&self) -> [bool; EYE_INPUTS] {
+    self.inputs
   }
 
-  pub(crate) fn step_vision(&self, dy: f64, position: na::Point2<f64>, target: na::Point2<f64>) -> Vec<f64> {
+  pub(crate) fn step_vision(&self, dy: f64, position: na::Point2<f64>, target: na::Point2<f64>, config: &SimulationConfig) -> Vec<f64> {
     // ypos of bird (dist from bot), y dist from top, y dist to bottom pipe, y dist to top pipe, x dist to target, yvel of bird
-    vec![
+    let vision = [
       position.y,
-      (target.y - PIPE_OFFSET_Y) - position.y,
-      (target.y + PIPE_OFFSET_Y) - position.y,
+      (target.y - config.pipe_offset_y) - position.y,
+      (target.y + config.pipe_offset_y) - position.y,
       target.x - position.x,
       dy,
-    ]
+    ];
+
+    vision
+      .into_iter()
+      .zip(self.inputs)
+      .map(|(value, enabled)| if enabled { value } else { 0.0 })
+      .collect()
   }
-}
\ No newline at end of file
+}
@@ -0,0 +1,137 @@
+use serde::{Serialize, Deserialize};
+
+use std::fmt;
+
+pub const PASS_REWARD: f64 = 20.0; // fitness per pipe cleared
+
+// what a bird made of its episode so far, or of its whole episode once dead
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Episode {
+  pub ticks: usize, // ticks alive
+  pub passes: usize,
+  pub flaps: usize,
+  pub distance: f64,
+  pub gap_distance: Option<f64>, // height from the centre of the next gap at death, none while alive
+}
+
+// shared by the episodes a population flies in parallel
+pub trait FitnessFunction: fmt::Debug + Send + Sync {
+  fn fitness(&self, episode: &Episode) -> f64;
+}
+
+impl<F> FitnessFunction for Box<F>
+  where F: FitnessFunction + ?Sized, {
+  fn fitness(&self, episode: &Episode) -> f64 {
+    (**self).fitness(episode)
+  }
+}
+
+// pipes cleared and nothing else, every early death ties at 0
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PipesPassed;
+
+impl FitnessFunction for PipesPassed {
+  fn fitness(&self, episode: &Episode) -> f64 {
+    PASS_REWARD * episode.passes as f64
+  }
+}
+
+// ticks survived, blind to pipes
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TicksAlive;
+
+impl FitnessFunction for TicksAlive {
+  fn fitness(&self, episode: &Episode) -> f64 {
+    episode.ticks as f64
+  }
+}
+
+// pipes cleared outweigh everything, distance then ranks birds that cleared
+// as many, and closeness to the gap ranks birds that died on the same tick;
+// closeness is worth less than a tick of distance, so it never outranks
+// flying on for one more tick
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Shaped;
+
+impl FitnessFunction for Shaped {
+  fn fitness(&self, episode: &Episode) -> f64 {
+    let closeness = match episode.gap_distance {
+      // every tick flies the same distance, `pipe_dx`
+      Some(gap_distance) if episode.ticks > 0 => {
+        let pipe_dx = episode.distance / episode.ticks as f64;
+        pipe_dx * (1.0 - gap_distance.min(1.0))
+      }
+      _ => 0.0,
+    };
+
+    PASS_REWARD * episode.passes as f64 + episode.distance + closeness
+  }
+}
+
+// `Shaped` minus a cost for every flap, favouring birds that glide
+#[derive(Clone, Copy, Debug)]
+pub struct FlapEconomy {
+  pub flap_cost: f64,
+}
+
+impl FitnessFunction for FlapEconomy {
+  fn fitness(&self, episode: &Episode) -> f64 {
+    (Shaped.fitness(episode) - self.flap_cost * episode.flaps as f64).max(0.0)
+  }
+}
+
+// a built-in fitness function as plain data, for config files and the web ui
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FitnessConfig {
+  PipesPassed,
+  TicksAlive,
+  #[default]
+  Shaped,
+  FlapEconomy { flap_cost: f64 },
+}
+
+impl FitnessConfig {
+  pub fn build(&self) -> Box<dyn FitnessFunction> {
+    match *self {
+      FitnessConfig::PipesPassed => Box::new(PipesPassed),
+      FitnessConfig::TicksAlive => Box::new(TicksAlive),
+      FitnessConfig::Shaped => Box::new(Shaped),
+      FitnessConfig::FlapEconomy { flap_cost } => Box::new(FlapEconomy { flap_cost }),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PIPE_DX: f64 = 0.0045;
+
+  fn episode(ticks: usize, gap_distance: Option<f64>) -> Episode {
+    Episode {
+      ticks,
+      passes: 0,
+      flaps: 0,
+      distance: ticks as f64 * PIPE_DX,
+      gap_distance,
+    }
+  }
+
+  #[test]
+  fn shaped_ranks_distance_before_closeness() {
+    let fitness = |episode| Shaped.fitness(&episode);
+
+    // died right at the gap, still behind a bird that died a tick later far off it
+    assert!(fitness(episode(100, Some(0.0))) < fitness(episode(101, Some(0.9))));
+    // on the same tick, closer to the gap is better
+    assert!(fitness(episode(100, Some(0.1))) > fitness(episode(100, Some(0.4))));
+    // a bird still flying gets no closeness at all
+    assert_eq!(fitness(episode(100, None)), 100.0 * PIPE_DX);
+  }
+
+  #[test]
+  fn shaped_ranks_passes_before_distance() {
+    let passed = Episode { passes: 1, ..episode(100, Some(0.9)) };
+    assert!(Shaped.fitness(&passed) > Shaped.fitness(&episode(500, Some(0.0))));
+  }
+}
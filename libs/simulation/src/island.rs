@@ -0,0 +1,211 @@
+use crate::*;
+
+// every island is a genetic algorithm of its own configuration
+pub type IslandGeneticAlgorithm = ga::IslandModel<
+  Box<dyn ga::DynSelectionMethod>,
+  Box<dyn ga::DynCrossoverMethod>,
+  Box<dyn ga::DynMutationMethod>,
+>;
+
+// several worlds trained side by side, one island of the model per world;
+// a generation ends once every world has run out of birds
+pub struct IslandSimulation {
+  configs: Vec<ga::GeneticAlgorithmConfig>, // one per island, as built
+  worlds: Vec<World>,
+  tick_count: usize,
+  generations: usize,
+  island_model: IslandGeneticAlgorithm,
+  history: Vec<Vec<ga::Statistics>>,
+}
+
+impl IslandSimulation {
+  // islands from cautious to adventurous, see `island_config`
+  pub fn random(rng: &mut impl RngCore, islands: usize) -> Self {
+    let configs: Vec<_> = (0..islands)
+      .map(|island| island_config(island, islands))
+      .collect();
+
+    Self::from_configs(rng, &configs).expect("Error - island configs are valid")
+  }
+
+  // one island per config, migrating the best 2 to the next island every 5 generations
+  pub fn from_configs(rng: &mut impl RngCore, configs: &[ga::GeneticAlgorithmConfig]) -> Result<Self, ga::ConfigError> {
+    let islands = configs
+      .iter()
+      .map(ga::GeneticAlgorithmConfig::build)
+      .collect::<Result<_, _>>()?;
+
+    let island_model = ga::IslandModel::new(islands, ga::MigrationTopology::Ring, 5, 2);
+    let worlds = configs
+      .iter()
+      .map(|_| World::random(rng))
+      .collect();
+
+    Ok(Self {
+      configs: configs.to_vec(),
+      worlds,
+      tick_count: 0,
+      generations: 0,
+      island_model,
+      history: Vec::new(),
+    })
+  }
+
+  pub fn step(&mut self, rng: &mut impl RngCore) -> bool {
+    let ticks = self.tick_count;
+    self.tick_count += 1;
+
+    for world in &mut self.worlds {
+      if !world.alive_birds.is_empty() {
+        world.step(ticks, rng);
+      }
+    }
+
+    if self.worlds.iter().all(|world| world.alive_birds.is_empty()) {
+      self.evolve(rng);
+
+      self.tick_count = 0;
+      self.generations += 1;
+
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn train(&mut self, rng: &mut impl RngCore) {
+    let new_tick_count = self.tick_count + 10000;
+    loop {
+      if self.step(rng) || self.tick_count >= new_tick_count {
+        return;
+      }
+    }
+  }
+
+  fn evolve(&mut self, rng: &mut impl RngCore) {
+    // prepare populations
+    let mut populations: Vec<Vec<_>> = self.worlds
+      .iter_mut()
+      .map(|world| world.birds_as_individuals().collect())
+      .collect();
+
+    // migrate and evolve populations
+    let stats = self.island_model.evolve(rng, self.generations, &mut populations);
+
+    // record generation
+    self.history.push(stats);
+
+    // bring back populations
+    for (world, population) in self.worlds.iter_mut().zip(populations) {
+      world.alive_birds = world.individuals_as_birds(population.into_iter(), rng).collect();
+
+      // reset environment
+      world.reset();
+    }
+  }
+}
+
+// config of island `island` out of `islands`: mutation grows island by island
+// from half to twice the default's, and every other island selects by
+// tournament rather than by roulette wheel
+pub fn island_config(island: usize, islands: usize) -> ga::GeneticAlgorithmConfig {
+  assert!(island < islands);
+
+  let spread = if islands > 1 { island as f64 / (islands - 1) as f64 } else { 0.5 };
+  let factor = 2.0_f64.powf(2.0 * spread - 1.0);
+
+  let defaults = ga::GeneticAlgorithmConfig::default();
+  let ga::MutationConfig::Gaussian { chance, mag } = defaults.mutation else {
+    unreachable!("Error - default mutation is gaussian")
+  };
+
+  ga::GeneticAlgorithmConfig {
+    selection: if island % 2 == 0 {
+      ga::SelectionConfig::RouletteWheel
+    } else {
+      ga::SelectionConfig::Tournament { size: 3 }
+    },
+    mutation: ga::MutationConfig::Gaussian {
+      chance: chance * factor,
+      mag: mag * factor,
+    },
+    ..defaults
+  }
+}
+
+impl IslandSimulation {
+  pub fn configs(&self) -> &[ga::GeneticAlgorithmConfig] {
+    &self.configs
+  }
+
+  pub fn worlds(&self) -> &[World] {
+    &self.worlds
+  }
+
+  pub fn tick_count(&self) -> usize {
+    self.tick_count
+  }
+
+  pub fn generations(&self) -> usize {
+    self.generations
+  }
+
+  // statistics of every island for every evolved generation, oldest first
+  pub fn history(&self) -> &[Vec<ga::Statistics>] {
+    &self.history
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn islands_are_configured_apart() {
+    let configs: Vec<_> = (0..3).map(|island| island_config(island, 3)).collect();
+
+    let mutation = |config: &ga::GeneticAlgorithmConfig| match config.mutation {
+      ga::MutationConfig::Gaussian { chance, mag } => (chance, mag),
+      _ => unreachable!(),
+    };
+    assert_eq!(mutation(&configs[0]), (0.0075, 0.15));
+    assert_eq!(mutation(&configs[1]), (0.015, 0.3));
+    assert_eq!(mutation(&configs[2]), (0.03, 0.6));
+
+    assert_eq!(configs[0].selection, ga::SelectionConfig::RouletteWheel);
+    assert_eq!(configs[1].selection, ga::SelectionConfig::Tournament { size: 3 });
+
+    // a lone island keeps the defaults
+    assert_eq!(island_config(0, 1), ga::GeneticAlgorithmConfig::default());
+  }
+
+  #[test]
+  fn every_island_evolves_its_own_world() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let mut simulation = IslandSimulation::random(&mut rng, 3);
+
+    assert_eq!(simulation.configs().len(), 3);
+    assert_eq!(simulation.worlds().len(), 3);
+
+    while !simulation.step(&mut rng) {}
+
+    assert_eq!(simulation.generations(), 1);
+    assert_eq!(simulation.history().len(), 1);
+    assert_eq!(simulation.history()[0].len(), 3);
+
+    let population_count = SimulationConfig::default().population_count;
+    for world in simulation.worlds() {
+      assert_eq!(world.alive_birds.len(), population_count);
+    }
+  }
+
+  #[test]
+  fn invalid_configs_are_rejected() {
+    let config = ga::GeneticAlgorithmConfig {
+      selection: ga::SelectionConfig::Tournament { size: 0 },
+      ..ga::GeneticAlgorithmConfig::default()
+    };
+
+    assert!(IslandSimulation::from_configs(&mut ChaCha8Rng::seed_from_u64(7), &[config]).is_err());
+  }
+}
@@ -1,8 +1,13 @@
 #![feature(extract_if)]
 
-use rand::RngCore;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use nalgebra as na;
 
+use std::io;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
 pub use lib_neural_network as nn;
 pub use lib_genetic_algorithm as ga;
 
@@ -11,7 +16,15 @@ mod bird;
 mod pipe;
 mod brain;
 mod world;
+mod island;
+mod steady;
+mod coevolution;
 mod rectangle;
+mod checkpoint;
+mod config;
+mod fitness;
+mod sweep;
+mod bird_traits;
 mod bird_individual;
 
 pub use self::{
@@ -20,7 +33,15 @@ pub use self::{
   pipe::*,
   brain::*,
   world::*,
+  island::*,
+  steady::*,
+  coevolution::*,
   rectangle::*,
+  checkpoint::*,
+  config::*,
+  fitness::*,
+  sweep::*,
+  bird_traits::*,
   bird_individual::*,
 };
 
@@ -38,29 +59,73 @@ pub trait AABB {
   }
 }
 
-pub struct Simulation {
+pub struct Simulation<O = ga::DefaultGeneticAlgorithm> {
   world: World,
   tick_count: usize,
   generations: usize,
-  genetic_alg: ga::DefaultGeneticAlgorithm,
+  optimizer: O,
+  history: Vec<ga::Statistics>,
+  pareto_front: Vec<Vec<f64>>, // objectives of the last evaluated generation's non-dominated birds
+  lineage: Option<ga::Lineage>,
+  traits_optimizer: Option<TraitsGeneticAlgorithm>,
+  observer: Box<dyn ga::Observer + Send>,
+  stopped: bool, // the observer broke out, nothing steps until another one is set
 }
 
 impl Simulation {
   pub fn random(rng: &mut impl RngCore) -> Self {
+    Self::with_optimizer(rng, ga::DefaultGeneticAlgorithm::default())
+  }
+
+  pub fn from_seed(seed: u64) -> (Self, ChaCha8Rng) {
+    Self::seeded(seed, ga::DefaultGeneticAlgorithm::default())
+  }
+}
+
+impl<O> Simulation<O>
+  where O: ga::Optimizer, {
+
+  pub fn with_optimizer(rng: &mut impl RngCore, optimizer: O) -> Self {
+    Self::with_config(rng, optimizer, SimulationConfig::default())
+  }
+
+  // stepping with the returned rng replays the same run on every platform,
+  // wasm included, as it is portable and seeded from nothing but `seed`
+  pub fn seeded(seed: u64, optimizer: O) -> (Self, ChaCha8Rng) {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let simulation = Self::with_optimizer(&mut rng, optimizer);
+
+    (simulation, rng)
+  }
+
+  pub fn with_config(rng: &mut impl RngCore, optimizer: O, config: SimulationConfig) -> Self {
     Self {
-      world: World::random(rng),
+      world: World::with_config(rng, config),
       tick_count: 0,
       generations: 0,
-      genetic_alg: ga::DefaultGeneticAlgorithm::default(),
+      optimizer,
+      history: Vec::new(),
+      pareto_front: Vec::new(),
+      lineage: None,
+      traits_optimizer: None,
+      observer: Box::new(()),
+      stopped: false,
     }
   }
 
   pub fn step(&mut self, rng: &mut impl RngCore) -> bool {
+    if self.stopped {
+      return false;
+    }
+
+    if self.tick_count == 0 {
+      self.observer.generation_start(self.generations);
+    }
+
     let ticks = self.tick_count;
     self.tick_count += 1;
 
-    self.prepare_world(ticks, rng);
-    self.step_world();
+    self.world.step(ticks, rng);
 
     if self.world.alive_birds.is_empty() {
       self.evolve(rng);
@@ -77,27 +142,96 @@ impl Simulation {
   pub fn train(&mut self, rng: &mut impl RngCore) {
     let new_tick_count = self.tick_count + 10000;
     loop {
-      if self.step(rng) || self.tick_count >= new_tick_count {
+      if self.stopped || self.step(rng) || self.tick_count >= new_tick_count {
         return;
       }
     }
   }
 
-  fn prepare_world(&mut self, ticks: usize, rng: &mut impl RngCore) {
-    // check collisions
-    self.world.collision();
+  // headless training from a fresh episode until `termination` holds; relies
+  // on the system clock, so it is native only
+  pub fn run<R: RngCore>(&mut self, rng: &mut R, termination: impl ga::Termination) -> ga::RunSummary {
+    let population: Vec<_> = self.world.birds_as_individuals().collect();
 
-    // spawn new pipes
-    if ticks % PIPE_TICK_GENERATION == 0 {
-      self.world.push_pipe(rng);
-    }
+    let world = &mut self.world;
+    let pareto_front = &mut self.pareto_front;
+    let lineage = &mut self.lineage;
+    let traits_optimizer = self.traits_optimizer.as_ref();
+    let mut observer = Offset {
+      observer: self.observer.as_mut(),
+      offset: self.generations,
+    };
+
+    let optimizer = Continued {
+      optimizer: &mut self.optimizer,
+      earlier: &self.history,
+    };
+
+    let mut driver = ga::Driver::new(optimizer, termination);
+    let (summary, next_population) = driver.run_with(
+      rng,
+      population,
+      |rng: &mut R, population| world.evaluate(population, rng),
+      |optimizer, rng, evaluated, observer| {
+        *pareto_front = objectives_of_pareto_front(evaluated);
+        next_generation(optimizer, lineage.as_mut(), traits_optimizer, observer, rng, evaluated)
+      },
+      &mut observer,
+    );
+
+    // bring back population
+    self.world.alive_birds = self.world.individuals_as_birds(next_population.into_iter(), rng).collect();
+    self.world.reset();
+
+    self.tick_count = 0;
+    self.generations += summary.generations;
+    self.history.extend(summary.history.iter().cloned());
+    self.stopped = summary.reason == ga::StopReason::Observer;
+
+    summary
+  }
+
+  // start recording where every following bird came from, with the current
+  // birds as founders
+  pub fn track_lineage(&mut self) {
+    let mut lineage = ga::Lineage::new();
+
+    self.world.alive_birds
+      .iter_mut()
+      .chain(self.world.dead_birds.iter_mut())
+      .for_each(|bird| bird.id = lineage.founder());
+
+    self.lineage = Some(lineage);
+  }
+
+  // evolve jump cooldown and eye inputs from now on too, instead of keeping
+  // every bird at the defaults
+  pub fn evolve_traits(&mut self) {
+    self.traits_optimizer = Some(BirdTraits::genetic_algorithm());
+  }
+
+  // score birds with `fitness` from now on, in place of the one picked in the
+  // config; a custom function is not saved with checkpoints, which resume with
+  // the config's, so prefer `set_fitness_config` for the built-in ones
+  pub fn set_fitness(&mut self, fitness: impl FitnessFunction + 'static) {
+    self.world.fitness = Arc::new(fitness);
   }
 
-  fn step_world(&mut self) {
-    // sense and perceive environment
-    self.world.decision();
-    // make movements
-    self.world.movement();
+  // score birds with a built-in fitness function from now on, recorded in the
+  // config so checkpoints resume with it
+  pub fn set_fitness_config(&mut self, fitness: FitnessConfig) -> io::Result<()> {
+    let config = SimulationConfig { fitness, ..self.world.config.clone() };
+    config.validate()?;
+
+    self.world.fitness = fitness.build().into();
+    self.world.config = config;
+    Ok(())
+  }
+
+  // report every following generation to `observer`, replacing any previous one
+  pub fn observe(&mut self, observer: impl ga::Observer + Send + 'static) {
+    self.observer = Box::new(observer);
+    self.stopped = false;
   }
 
   fn evolve(&mut self, rng: &mut impl RngCore) {
@@ -105,28 +239,166 @@ impl Simulation {
     let current_population: Vec<_> = self.world.birds_as_individuals().collect();
 
     // evolve population
-    let (evolved_population, _) = self.genetic_alg.evolve(rng, &current_population);
-    let collected: Vec<_> = evolved_population.collect();
+    self.pareto_front = objectives_of_pareto_front(&current_population);
+    let (collected, stats) = next_generation(
+      &mut self.optimizer,
+      self.lineage.as_mut(),
+      self.traits_optimizer.as_ref(),
+      self.observer.as_mut(),
+      rng,
+      &current_population,
+    );
+
+    // record generation
+    self.stopped = self.observer.generation_end(self.generations, &stats).is_break();
+    self.history.push(stats);
+    self.optimizer.adapt(&self.history);
 
     // bring back population
     self.world.alive_birds = self.world.individuals_as_birds(collected.into_iter(), rng).collect();
 
     // reset environment
-    self.reset();
+    self.world.reset();
   }
+}
 
-  fn reset(&mut self) {
-    self.world.pipes.clear();
-    self.world.dead_birds.clear();
+// one generation of brains, and of traits when those evolve too
+fn next_generation<O>(
+  optimizer: &mut O,
+  mut lineage: Option<&mut ga::Lineage>,
+  traits_optimizer: Option<&TraitsGeneticAlgorithm>,
+  observer: &mut dyn ga::Observer,
+  rng: &mut impl RngCore,
+  population: &[BirdIndividual],
+) -> (Vec<BirdIndividual>, ga::Statistics)
+  where O: ga::Optimizer, {
+  if let Some(lineage) = &mut lineage {
+    lineage.advance();
   }
+
+  let traits = traits_optimizer.map(|ga| TraitsBreeder::new(ga, population));
+
+  optimizer.next_generation_with(rng, population, observer, &mut (lineage, traits))
 }
 
-impl Simulation {
+fn objectives_of_pareto_front(population: &[BirdIndividual]) -> Vec<Vec<f64>> {
+  ga::pareto_front(population)
+    .into_iter()
+    .map(|bird| bird.objectives.clone())
+    .collect()
+}
+
+// adapts the optimizer of a run to the generations evolved before it too, so
+// schedules see the same history whether the simulation is stepped or run
+struct Continued<'a, O> {
+  optimizer: &'a mut O,
+  earlier: &'a [ga::Statistics],
+}
+
+impl<O> ga::Optimizer for Continued<'_, O>
+  where O: ga::Optimizer, {
+
+  fn next_generation<I>(&mut self, rng: &mut impl RngCore, population: &[I]) -> (Vec<I>, ga::Statistics)
+    where I: ga::Individual<Gene = f64> {
+    self.optimizer.next_generation(rng, population)
+  }
+
+  fn next_generation_with<I>(
+    &mut self,
+    rng: &mut impl RngCore,
+    population: &[I],
+    observer: &mut dyn ga::Observer,
+    hook: &mut dyn ga::BreedHook<I>,
+  ) -> (Vec<I>, ga::Statistics)
+    where I: ga::Individual<Gene = f64> {
+    self.optimizer.next_generation_with(rng, population, observer, hook)
+  }
+
+  fn adapt(&mut self, history: &[ga::Statistics]) {
+    let history: Vec<_> = self.earlier.iter().chain(history).cloned().collect();
+    self.optimizer.adapt(&history);
+  }
+}
+
+// numbers a run's generations on from those evolved before it, so observers
+// count the same whether the simulation is stepped or run
+struct Offset<'a> {
+  observer: &'a mut dyn ga::Observer,
+  offset: usize,
+}
+
+impl ga::Observer for Offset<'_> {
+  fn generation_start(&mut self, generation: usize) {
+    self.observer.generation_start(self.offset + generation)
+  }
+
+  fn after_selection(&mut self, parent_a: &ga::Chromosome, parent_b: &ga::Chromosome) {
+    self.observer.after_selection(parent_a, parent_b)
+  }
+
+  fn after_offspring(&mut self, child: &ga::Chromosome) {
+    self.observer.after_offspring(child)
+  }
+
+  fn generation_end(&mut self, generation: usize, stats: &ga::Statistics) -> ControlFlow<()> {
+    self.observer.generation_end(self.offset + generation, stats)
+  }
+}
+
+impl<O> Simulation<O> {
   pub fn world(&self) -> &World {
     &self.world
   }
 
+  pub fn config(&self) -> &SimulationConfig {
+    self.world.config()
+  }
+
   pub fn tick_count(&self) -> usize {
     self.tick_count
   }
+
+  pub fn generations(&self) -> usize {
+    self.generations
+  }
+
+  // statistics of every evolved generation, oldest first
+  pub fn history(&self) -> &[ga::Statistics] {
+    &self.history
+  }
+
+  // pipes passed, distance per flap and negated brain complexity of every bird
+  // of the last evolved generation that no other bird beat on all three
+  pub fn pareto_front(&self) -> &[Vec<f64>] {
+    &self.pareto_front
+  }
+
+  pub fn optimizer(&self) -> &O {
+    &self.optimizer
+  }
+
+  pub fn lineage(&self) -> Option<&ga::Lineage> {
+    self.lineage.as_ref()
+  }
+
+  // whether the observer stopped evolution
+  pub fn is_stopped(&self) -> bool {
+    self.stopped
+  }
+
+  // hash of every generation's statistics and of the birds in flight, for
+  // checking that a seeded run replays bit for bit on another platform
+  pub fn fingerprint(&self) -> u64 {
+    let stats = self.history
+      .iter()
+      .flat_map(|stats| [stats.min_fitness(), stats.avg_fitness(), stats.max_fitness(), stats.diversity()]);
+    let birds = self.world
+      .birds()
+      .flat_map(|bird| [bird.position().y, bird.dy()]);
+
+    // fnv-1a over the bits, std's hashers are not guaranteed stable
+    stats
+      .chain(birds)
+      .fold(0xcbf2_9ce4_8422_2325, |hash, value| (hash ^ value.to_bits()).wrapping_mul(0x0100_0000_01b3))
+  }
 }
\ No newline at end of file
@@ -3,11 +3,6 @@ use nalgebra as na;
 
 use crate::*;
 
-pub const PIPE_DX: f64 = 0.0045;
-pub const PIPE_OFFSET_X: f64 = 0.044; // pipe/gap width = 2*PIPE_OFFSET_X
-pub const PIPE_OFFSET_Y: f64 = 0.13; // pipe gap height = 2*PIPE_OFFSET_Y
-pub const PIPE_TICK_GENERATION: usize = 120;
-
 #[derive(Debug, Clone)]
 pub struct Pipe {
   // |     |
@@ -22,26 +17,31 @@ pub struct Pipe {
 }
 
 impl Pipe {
-  pub(crate) fn random(rng: &mut impl RngCore) -> Self {
-    let x = 1.0 + PIPE_OFFSET_X;
-    let y = rng.gen::<f64>().max(PIPE_OFFSET_Y).min(1.0 - PIPE_OFFSET_Y);
+  pub(crate) fn random(rng: &mut impl RngCore, config: &SimulationConfig) -> Self {
+    Self::with_gap(rng.gen(), config)
+  }
+
+  // gap centred at height `y`, kept fully on screen
+  pub(crate) fn with_gap(y: f64, config: &SimulationConfig) -> Self {
+    let x = 1.0 + config.pipe_offset_x;
+    let y = y.max(config.pipe_offset_y).min(1.0 - config.pipe_offset_y);
 
     Self {
       position: na::Point2::new(x, y),
     }
   }
 
-  pub(crate) fn collision(&self) -> bool {
-    self.position.x <= -PIPE_OFFSET_X
+  pub(crate) fn collision(&self, config: &SimulationConfig) -> bool {
+    self.position.x <= -config.pipe_offset_x
   }
 
-  pub(crate) fn movement(&mut self) {
-    self.position.x += -PIPE_DX;
+  pub(crate) fn movement(&mut self, config: &SimulationConfig) {
+    self.position.x += -config.pipe_dx;
   }
 
-  pub(crate) fn top_rectangle(&self) -> Rectangle {
-    let position = na::Point2::new(self.position.x - PIPE_OFFSET_X, self.position.y + PIPE_OFFSET_Y);
-    let width = 2.0 * PIPE_OFFSET_X;
+  pub(crate) fn top_rectangle(&self, config: &SimulationConfig) -> Rectangle {
+    let position = na::Point2::new(self.position.x - config.pipe_offset_x, self.position.y + config.pipe_offset_y);
+    let width = 2.0 * config.pipe_offset_x;
     let height = 1.0 - position.y;
 
     Rectangle { 
@@ -51,10 +51,10 @@ impl Pipe {
     }
   }
 
-  pub(crate) fn bot_rectangle(&self) -> Rectangle {
-    let position = na::Point2::new(self.position.x - PIPE_OFFSET_X, 0.0);
-    let width = 2.0 * PIPE_OFFSET_X;
-    let height = self.position.y - PIPE_OFFSET_Y;
+  pub(crate) fn bot_rectangle(&self, config: &SimulationConfig) -> Rectangle {
+    let position = na::Point2::new(self.position.x - config.pipe_offset_x, 0.0);
+    let width = 2.0 * config.pipe_offset_x;
+    let height = self.position.y - config.pipe_offset_y;
 
     Rectangle { 
       position,
@@ -0,0 +1,173 @@
+use crate::*;
+
+use std::mem;
+
+const POOL_CAPACITY: usize = 70;
+
+// birds keep flying continuously: every bird that dies is remembered in a pool
+// of evaluated birds and immediately replaced by a child bred from that pool
+pub struct SteadyStateSimulation {
+  world: World,
+  tick_count: usize,
+  births: usize,
+  genetic_alg: ga::DefaultGeneticAlgorithm,
+  policy: ga::ReplacementPolicy,
+  layers: Option<ga::AgeLayers>,
+  pool: Vec<BirdIndividual>,
+}
+
+impl SteadyStateSimulation {
+  pub fn random(rng: &mut impl RngCore) -> Self {
+    Self::new(rng, ga::ReplacementPolicy::Worst, None)
+  }
+
+  pub fn new(rng: &mut impl RngCore, policy: ga::ReplacementPolicy, layers: Option<ga::AgeLayers>) -> Self {
+    Self {
+      world: World::random(rng),
+      tick_count: 0,
+      births: 0,
+      genetic_alg: ga::DefaultGeneticAlgorithm::default(),
+      policy,
+      layers,
+      pool: Vec::new(),
+    }
+  }
+
+  // returns whether any bird was born this tick
+  pub fn step(&mut self, rng: &mut impl RngCore) -> bool {
+    let ticks = self.tick_count;
+    self.tick_count += 1;
+
+    self.world.step(ticks, rng);
+
+    let dead_birds = mem::take(&mut self.world.dead_birds);
+    let born = !dead_birds.is_empty();
+
+    for bird in dead_birds {
+      self.remember(bird.into(), rng);
+
+      let child = self.breed(rng);
+      self.world.alive_birds.push(child);
+    }
+
+    born
+  }
+
+  pub fn train(&mut self, rng: &mut impl RngCore) {
+    for _ in 0..10000 {
+      self.step(rng);
+    }
+  }
+
+  fn remember(&mut self, individual: BirdIndividual, rng: &mut impl RngCore) {
+    if self.pool.len() < POOL_CAPACITY {
+      self.pool.push(individual);
+      return;
+    }
+
+    // with age layers, newcomers only displace birds of their own layer
+    let candidates: Vec<_> = match &self.layers {
+      Some(layers) => layers.members(&self.pool, layers.layer(individual.age)),
+      None => Vec::new(),
+    };
+
+    let victim = if candidates.is_empty() {
+      self.policy.victim(rng, &self.pool)
+    } else {
+      self.policy.victim_among(rng, &self.pool, &candidates)
+    };
+
+    self.pool[victim] = individual;
+  }
+
+  fn breed(&mut self, rng: &mut impl RngCore) -> Bird {
+    let mut bird = self.conceive(rng);
+    bird.spare(&self.world.config);
+    bird
+  }
+
+  fn conceive(&mut self, rng: &mut impl RngCore) -> Bird {
+    self.births += 1;
+
+    if self.pool.is_empty() {
+      return Bird::random(rng, &self.world.config);
+    }
+
+    let offspring = match &self.layers {
+      // layers take turns, and an empty bottom layer is refilled with random birds
+      Some(layers) => {
+        let layer = self.births % layers.layers();
+        self.genetic_alg.layered_offspring(rng, layers, layer, &self.pool)
+      }
+      None => Some(self.genetic_alg.offspring(rng, &self.pool)),
+    };
+
+    match offspring {
+      Some((individual, age)) => {
+        let mut bird = individual.into_bird(rng, &self.world.config);
+        bird.age = age;
+        bird
+      }
+      None => Bird::random(rng, &self.world.config),
+    }
+  }
+}
+
+impl SteadyStateSimulation {
+  pub fn world(&self) -> &World {
+    &self.world
+  }
+
+  pub fn tick_count(&self) -> usize {
+    self.tick_count
+  }
+
+  pub fn births(&self) -> usize {
+    self.births
+  }
+
+  pub fn pool_statistics(&self) -> Option<ga::Statistics> {
+    (!self.pool.is_empty()).then(|| ga::Statistics::new(&self.pool))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn newborns_are_spared_by_pipes_until_one_has_passed() {
+    let config = SimulationConfig::default();
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+
+    let mut pipe = Pipe::with_gap(0.5, &config);
+    pipe.position.x = config.bird_x;
+
+    let mut bird = Bird::random(&mut rng, &config);
+    bird.spare(&config);
+    let grace = bird.grace;
+    assert!(grace as f64 * config.pipe_dx >= 2.0 * config.pipe_offset_x);
+
+    // inside the top pipe, clear of the screen edges
+    let inside = na::Point2::new(config.bird_x, 0.5 + config.pipe_offset_y + 0.1);
+    for _ in 0..grace {
+      bird.position = inside;
+      assert!(!bird.collision(Some(&pipe), &config));
+      bird.movement(&config);
+    }
+
+    bird.position = inside;
+    assert!(bird.collision(Some(&pipe), &config));
+  }
+
+  #[test]
+  fn children_are_born_spared() {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    let mut sim = SteadyStateSimulation::random(&mut rng);
+
+    while !sim.step(&mut rng) {}
+
+    let newborn = sim.world.alive_birds.last().expect("Error - no bird was born");
+    assert!(newborn.grace > 0);
+  }
+}
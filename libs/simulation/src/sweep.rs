@@ -0,0 +1,505 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::*;
+
+// genes a point of the search space is encoded in, for random search and meta-evolution
+pub const PARAMS_GENES: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum OptimizerKind {
+  GeneticAlgorithm,
+  DifferentialEvolution,
+  EvolutionStrategyPlus,
+  EvolutionStrategyComma,
+  CmaEs,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum MutationKind {
+  Gaussian,
+  Normal,
+  Cauchy,
+}
+
+// one setting of an optimizer to try out; values the optimizer does not use
+// are left at the first of their search space
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Params {
+  pub population: usize,
+  pub optimizer: OptimizerKind,
+  pub mutation: MutationKind, // genetic algorithm and evolution strategies
+  pub chance: f64, // genetic algorithm and evolution strategies
+  pub magnitude: f64, // range, standard deviation or scale, depending on `mutation`; sigma of cma-es
+  pub scaling: ga::FitnessScaling, // genetic algorithm
+  pub weight: f64, // differential evolution
+  pub crossover_rate: f64, // differential evolution
+  pub mu: usize, // evolution strategies
+}
+
+impl Params {
+  pub fn config(&self) -> ga::OptimizerConfig {
+    match self.optimizer {
+      OptimizerKind::GeneticAlgorithm => ga::GeneticAlgorithmConfig {
+        mutation: self.mutation(),
+        scaling: self.scaling,
+        ..ga::GeneticAlgorithmConfig::default()
+      }.into(),
+      OptimizerKind::DifferentialEvolution => ga::OptimizerConfig::DifferentialEvolution {
+        variant: ga::DifferentialVariant::Rand1Bin,
+        weight: self.weight,
+        crossover_rate: self.crossover_rate,
+      },
+      OptimizerKind::EvolutionStrategyPlus => self.evolution_strategy(ga::Replacement::Plus),
+      OptimizerKind::EvolutionStrategyComma => self.evolution_strategy(ga::Replacement::Comma),
+      OptimizerKind::CmaEs => ga::OptimizerConfig::CmaEs { sigma: self.magnitude },
+    }
+  }
+
+  fn mutation(&self) -> ga::MutationConfig {
+    let (chance, magnitude) = (self.chance, self.magnitude);
+    match self.mutation {
+      MutationKind::Gaussian => ga::MutationConfig::Gaussian { chance, mag: magnitude },
+      MutationKind::Normal => ga::MutationConfig::Normal { chance, sigma: magnitude },
+      MutationKind::Cauchy => ga::MutationConfig::Cauchy { chance, scale: magnitude },
+    }
+  }
+
+  fn evolution_strategy(&self, replacement: ga::Replacement) -> ga::OptimizerConfig {
+    ga::OptimizerConfig::EvolutionStrategy {
+      mu: self.mu,
+      replacement,
+      mutation: self.mutation(),
+    }
+  }
+}
+
+// values to search over; a grid tries every combination of the values each
+// optimizer uses, random search and meta-evolution go anywhere between the
+// extremes of the numeric values
+#[derive(Clone, Debug)]
+pub struct SearchSpace {
+  pub populations: Vec<usize>,
+  pub optimizers: Vec<OptimizerKind>,
+  pub mutations: Vec<MutationKind>,
+  pub chances: Vec<f64>,
+  pub magnitudes: Vec<f64>,
+  pub scalings: Vec<ga::FitnessScaling>,
+  pub weights: Vec<f64>,
+  pub crossover_rates: Vec<f64>,
+  pub mus: Vec<usize>,
+}
+
+impl SearchSpace {
+  pub fn grid(&self) -> Vec<Params> {
+    let mut grid = Vec::new();
+
+    for &population in &self.populations {
+      for &optimizer in &self.optimizers {
+        let first = Params {
+          population,
+          optimizer,
+          mutation: self.mutations[0],
+          chance: self.chances[0],
+          magnitude: self.magnitudes[0],
+          scaling: self.scalings[0],
+          weight: self.weights[0],
+          crossover_rate: self.crossover_rates[0],
+          mu: self.mus[0],
+        };
+        let settings = vec![first];
+
+        let settings = match optimizer {
+          OptimizerKind::GeneticAlgorithm => {
+            let settings = vary(settings, &self.mutations, |params, mutation| params.mutation = mutation);
+            let settings = vary(settings, &self.chances, |params, chance| params.chance = chance);
+            let settings = vary(settings, &self.magnitudes, |params, magnitude| params.magnitude = magnitude);
+            vary(settings, &self.scalings, |params, scaling| params.scaling = scaling)
+          }
+          OptimizerKind::DifferentialEvolution => {
+            let settings = vary(settings, &self.weights, |params, weight| params.weight = weight);
+            vary(settings, &self.crossover_rates, |params, rate| params.crossover_rate = rate)
+          }
+          OptimizerKind::EvolutionStrategyPlus | OptimizerKind::EvolutionStrategyComma => {
+            let settings = vary(settings, &self.mus, |params, mu| params.mu = mu);
+            let settings = vary(settings, &self.mutations, |params, mutation| params.mutation = mutation);
+            let settings = vary(settings, &self.chances, |params, chance| params.chance = chance);
+            vary(settings, &self.magnitudes, |params, magnitude| params.magnitude = magnitude)
+          }
+          OptimizerKind::CmaEs => vary(settings, &self.magnitudes, |params, magnitude| params.magnitude = magnitude),
+        };
+
+        grid.extend(settings);
+      }
+    }
+
+    grid
+  }
+
+  pub fn random(&self, rng: &mut impl RngCore, count: usize) -> Vec<Params> {
+    (0..count)
+      .map(|_| {
+        let genes: Vec<f64> = (0..PARAMS_GENES).map(|_| rng.gen()).collect();
+        self.params(&genes)
+      })
+      .collect()
+  }
+
+  // point of the space encoded by `PARAMS_GENES` genes within 0..=1
+  pub fn params(&self, genes: &[f64]) -> Params {
+    assert_eq!(genes.len(), PARAMS_GENES);
+
+    let populations: Vec<_> = self.populations.iter().map(|&population| population as f64).collect();
+    let mus: Vec<_> = self.mus.iter().map(|&mu| mu as f64).collect();
+
+    Params {
+      population: between(&populations, genes[0]).round() as usize,
+      optimizer: pick(&self.optimizers, genes[1]),
+      mutation: pick(&self.mutations, genes[2]),
+      chance: between(&self.chances, genes[3]),
+      magnitude: between(&self.magnitudes, genes[4]),
+      scaling: pick(&self.scalings, genes[5]),
+      weight: between(&self.weights, genes[6]),
+      crossover_rate: between(&self.crossover_rates, genes[7]),
+      mu: between(&mus, genes[8]).round() as usize,
+    }
+  }
+}
+
+impl Default for SearchSpace {
+  fn default() -> Self {
+    Self {
+      populations: vec![35, 70, 140],
+      optimizers: vec![
+        OptimizerKind::GeneticAlgorithm,
+        OptimizerKind::DifferentialEvolution,
+        OptimizerKind::EvolutionStrategyPlus,
+        OptimizerKind::EvolutionStrategyComma,
+        OptimizerKind::CmaEs,
+      ],
+      mutations: vec![MutationKind::Gaussian, MutationKind::Normal, MutationKind::Cauchy],
+      chances: vec![0.005, 0.015, 0.05],
+      magnitudes: vec![0.1, 0.3, 0.6],
+      scalings: vec![ga::FitnessScaling::Identity, ga::FitnessScaling::Linear { multiple: 2.0 }],
+      weights: vec![0.5, 0.8],
+      crossover_rates: vec![0.3, 0.9],
+      mus: vec![5, 15],
+    }
+  }
+}
+
+// every setting with every value in turn
+fn vary<T: Copy>(settings: Vec<Params>, values: &[T], set: impl Fn(&mut Params, T)) -> Vec<Params> {
+  let mut varied = Vec::with_capacity(settings.len() * values.len());
+
+  for params in settings {
+    for &value in values {
+      let mut params = params;
+      set(&mut params, value);
+      varied.push(params);
+    }
+  }
+
+  varied
+}
+
+fn between(values: &[f64], gene: f64) -> f64 {
+  let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+  let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+  min + gene.clamp(0.0, 1.0) * (max - min)
+}
+
+fn pick<T: Copy>(values: &[T], gene: f64) -> T {
+  let index = (gene.clamp(0.0, 1.0) * values.len() as f64) as usize;
+  values[index.min(values.len() - 1)]
+}
+
+// one headless training run of one setting with one seed
+#[derive(Clone, Debug, Serialize)]
+pub struct Trial {
+  pub params: Params,
+  pub seed: u64,
+  pub generations: usize,
+  pub best_fitness: f64,
+  pub final_avg_fitness: f64,
+  pub seconds: f64,
+}
+
+// trains a fresh `Simulation` for every setting and seed, a fixed number of
+// generations each so settings stay comparable
+#[derive(Clone, Debug)]
+pub struct Sweep {
+  pub seeds: Vec<u64>,
+  pub generations: usize,
+  pub config: SimulationConfig, // world of every trial, the population size aside
+}
+
+impl Sweep {
+  pub fn trial(&self, params: Params, seed: u64) -> Trial {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let config = SimulationConfig {
+      population_count: params.population,
+      ..self.config.clone()
+    };
+    let optimizer = params.config()
+      .build()
+      .expect("Error - sweep settings stay within what the operators accept");
+    let mut simulation = Simulation::with_config(&mut rng, optimizer, config);
+
+    let start = Instant::now();
+    let summary = simulation.run(&mut rng, ga::MaxGenerations(self.generations));
+
+    Trial {
+      params,
+      seed,
+      generations: summary.generations,
+      best_fitness: summary.best_fitness,
+      final_avg_fitness: summary.history.last().map_or(0.0, ga::Statistics::avg_fitness),
+      seconds: start.elapsed().as_secs_f64(),
+    }
+  }
+
+  // every setting with every seed, handing each finished trial to `progress`
+  pub fn run(&self, settings: &[Params], mut progress: impl FnMut(&Trial)) -> Vec<Trial> {
+    settings
+      .iter()
+      .flat_map(|&params| self.seeds.iter().map(move |&seed| (params, seed)))
+      .map(|(params, seed)| {
+        let trial = self.trial(params, seed);
+        progress(&trial);
+        trial
+      })
+      .collect()
+  }
+
+  // tunes the settings with an outer genetic algorithm, every setting scoring
+  // its mean best fitness over the seeds; returns every trial it ran
+  pub fn meta_evolve(
+    &self,
+    rng: &mut impl RngCore,
+    space: &SearchSpace,
+    population: usize,
+    generations: usize,
+    mut progress: impl FnMut(&Trial),
+  ) -> Vec<Trial> {
+    let meta_alg = ga::GeneticAlgorithm::new(
+      ga::RouletteWheelSelection::new(),
+      ga::UniformCrossover::new(),
+      ga::BoundedMutation::new(
+        ga::GaussianMutation::new(0.2, 0.15),
+        ga::GeneBounds::uniform(0.0, 1.0, ga::BoundaryHandling::Reflect),
+      ),
+    );
+
+    let settings: Vec<_> = (0..population)
+      .map(|_| {
+        (0..PARAMS_GENES)
+          .map(|_| rng.gen())
+          .collect::<ga::Chromosome>()
+          .into()
+      })
+      .collect();
+
+    let mut trials = Vec::new();
+    let evaluate = |_: &mut _, settings: Vec<MetaIndividual>| {
+      settings
+        .into_iter()
+        .map(|mut setting| {
+          let genes: Vec<_> = setting.chromosome.iter().copied().collect();
+          let setting_trials = self.run(&[space.params(&genes)], &mut progress);
+
+          setting.fitness = setting_trials.iter().map(|trial| trial.best_fitness).sum::<f64>() / setting_trials.len() as f64;
+          trials.extend(setting_trials);
+          setting
+        })
+        .collect()
+    };
+
+    ga::Driver::new(meta_alg, ga::MaxGenerations(generations)).run(rng, settings, evaluate);
+
+    trials
+  }
+}
+
+// setting of the inner genetic algorithm, as evolved by the outer one
+struct MetaIndividual {
+  fitness: f64,
+  chromosome: ga::Chromosome,
+}
+
+impl ga::Individual for MetaIndividual {
+  type Gene = f64;
+
+  fn fitness(&self) -> f64 {
+    self.fitness
+  }
+}
+
+impl AsRef<ga::Chromosome> for MetaIndividual {
+  fn as_ref(&self) -> &ga::Chromosome {
+    &self.chromosome
+  }
+}
+
+impl From<ga::Chromosome> for MetaIndividual {
+  fn from(chromosome: ga::Chromosome) -> Self {
+    Self {
+      fitness: 0.0,
+      chromosome,
+    }
+  }
+}
+
+pub fn write_csv(trials: &[Trial], mut writer: impl Write) -> io::Result<()> {
+  writeln!(writer, "population,optimizer,mutation,chance,magnitude,scaling,weight,crossover_rate,mu,seed,generations,best_fitness,final_avg_fitness,seconds")?;
+
+  for trial in trials {
+    let params = &trial.params;
+    writeln!(
+      writer,
+      "{},{:?},{:?},{},{},\"{:?}\",{},{},{},{},{},{},{},{:.3}",
+      params.population,
+      params.optimizer,
+      params.mutation,
+      params.chance,
+      params.magnitude,
+      params.scaling,
+      params.weight,
+      params.crossover_rate,
+      params.mu,
+      trial.seed,
+      trial.generations,
+      trial.best_fitness,
+      trial.final_avg_fitness,
+      trial.seconds,
+    )?;
+  }
+
+  Ok(())
+}
+
+pub fn write_json(trials: &[Trial], writer: impl Write) -> io::Result<()> {
+  serde_json::to_writer_pretty(writer, trials).map_err(io::Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn space(optimizers: Vec<OptimizerKind>) -> SearchSpace {
+    SearchSpace {
+      populations: vec![35, 70],
+      optimizers,
+      ..SearchSpace::default()
+    }
+  }
+
+  #[test]
+  fn grid_varies_only_what_each_optimizer_uses() {
+    let count = |optimizer| space(vec![optimizer]).grid().len();
+
+    assert_eq!(count(OptimizerKind::GeneticAlgorithm), 2 * 3 * 3 * 3 * 2);
+    assert_eq!(count(OptimizerKind::DifferentialEvolution), 2 * 2 * 2);
+    assert_eq!(count(OptimizerKind::EvolutionStrategyPlus), 2 * 2 * 3 * 3 * 3);
+    assert_eq!(count(OptimizerKind::CmaEs), 2 * 3);
+  }
+
+  #[test]
+  fn grid_has_no_duplicates() {
+    let grid = SearchSpace::default().grid();
+
+    for (i, params) in grid.iter().enumerate() {
+      assert!(!grid[i + 1..].contains(params), "{params:?} comes up twice");
+    }
+  }
+
+  #[test]
+  fn every_setting_builds() {
+    let space = SearchSpace::default();
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+    for params in space.grid().into_iter().chain(space.random(&mut rng, 50)) {
+      assert!(params.config().build().is_ok(), "{params:?} does not build");
+    }
+  }
+
+  #[test]
+  fn genes_span_the_extremes() {
+    let space = SearchSpace::default();
+
+    let low = space.params(&[0.0; PARAMS_GENES]);
+    assert_eq!((low.population, low.optimizer, low.mu), (35, OptimizerKind::GeneticAlgorithm, 5));
+
+    let high = space.params(&[1.0; PARAMS_GENES]);
+    assert_eq!((high.population, high.optimizer, high.mu), (140, OptimizerKind::CmaEs, 15));
+  }
+
+  fn trial() -> Trial {
+    let params = Params {
+      scaling: ga::FitnessScaling::Linear { multiple: 2.0 },
+      ..SearchSpace::default().params(&[0.0; PARAMS_GENES])
+    };
+
+    Trial {
+      params,
+      seed: 7,
+      generations: 3,
+      best_fitness: 41.5,
+      final_avg_fitness: 12.25,
+      seconds: 0.12345,
+    }
+  }
+
+  #[test]
+  fn csv_has_a_row_per_trial_matching_the_header() {
+    let mut csv = Vec::new();
+    write_csv(&[trial(), trial()], &mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[1], lines[2]);
+
+    // the scaling is quoted, its debug output has commas of its own
+    let (before, rest) = lines[1].split_once('"').unwrap();
+    let (scaling, after) = rest.split_once('"').unwrap();
+    assert_eq!(scaling, "Linear { multiple: 2.0 }");
+
+    let fields = before.split(',').count() + after.split(',').count() - 1;
+    assert_eq!(fields, lines[0].split(',').count());
+    assert!(after.ends_with(",7,3,41.5,12.25,0.123"));
+  }
+
+  #[test]
+  fn json_keeps_every_field() {
+    let mut json = Vec::new();
+    write_json(&[trial()], &mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+
+    assert_eq!(json[0]["seed"], 7);
+    assert_eq!(json[0]["best_fitness"], 41.5);
+    assert_eq!(json[0]["params"]["optimizer"], "GeneticAlgorithm");
+    assert_eq!(json[0]["params"]["scaling"]["Linear"]["multiple"], 2.0);
+  }
+
+  #[test]
+  fn runs_every_setting_with_every_seed() {
+    let sweep = Sweep {
+      seeds: vec![1, 2],
+      generations: 1,
+      config: SimulationConfig::default(),
+    };
+    let settings = [trial().params, SearchSpace::default().params(&[1.0; PARAMS_GENES])];
+
+    let mut reported = 0;
+    let trials = sweep.run(&settings, |_| reported += 1);
+
+    assert_eq!(trials.len(), 4);
+    assert_eq!(reported, 4);
+    assert!(trials.iter().all(|trial| trial.generations == 1));
+  }
+}
@@ -1,29 +1,154 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
 use crate::*;
 
 use std::mem;
 use std::iter;
+use std::sync::Arc;
 use std::collections::VecDeque;
 
-const POPULATION_COUNT: usize = 70;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub(crate) const EPISODE_TICK_LIMIT: usize = 100_000; // stops flawless birds from flying forever
 
 #[derive(Debug)]
 pub struct World {
   pub alive_birds: Vec<Bird>,
   pub dead_birds: Vec<Bird>,
   pub pipes: VecDeque<Pipe>,
+  pub(crate) course: Vec<f64>, // gap heights pipes spawn at in turn, random when empty
+  pub(crate) pipes_spawned: usize,
+  pub(crate) config: SimulationConfig,
+  pub(crate) fitness: Arc<dyn FitnessFunction>, // built from `config` unless replaced
 }
 
 impl World {
   pub(crate) fn random(rng: &mut impl RngCore) -> Self {
-    let alive_birds = iter::from_fn(|| Some(Bird::random(rng)))
-      .take(POPULATION_COUNT)
+    Self::with_config(rng, SimulationConfig::default())
+  }
+
+  pub(crate) fn with_config(rng: &mut impl RngCore, config: SimulationConfig) -> Self {
+    let alive_birds = iter::from_fn(|| Some(Bird::random(rng, &config)))
+      .take(config.population_count)
+      .enumerate()
+      .map(|(index, mut bird)| {
+        bird.index = index;
+        bird
+      })
       .collect();
 
     Self { 
       alive_birds,
       dead_birds: Vec::new(),
       pipes: VecDeque::new(),
+      course: Vec::new(),
+      pipes_spawned: 0,
+      fitness: config.fitness.build().into(),
+      config,
+    }
+  }
+
+  // birdless world whose pipes follow `course`
+  pub(crate) fn with_course(course: Vec<f64>, config: SimulationConfig) -> Self {
+    Self {
+      alive_birds: Vec::new(),
+      dead_birds: Vec::new(),
+      pipes: VecDeque::new(),
+      course,
+      pipes_spawned: 0,
+      fitness: config.fitness.build().into(),
+      config,
+    }
+  }
+
+  // fresh episode over the same course, scored the same way, for `birds`
+  fn episode(&self, birds: Vec<Bird>) -> Self {
+    Self {
+      alive_birds: birds,
+      dead_birds: Vec::new(),
+      pipes: VecDeque::new(),
+      course: self.course.clone(),
+      pipes_spawned: 0,
+      fitness: Arc::clone(&self.fitness),
+      config: self.config.clone(),
+    }
+  }
+
+  pub(crate) fn step(&mut self, ticks: usize, rng: &mut impl RngCore) {
+    // check collisions
+    self.collision();
+
+    // spawn new pipes
+    if ticks % self.config.pipe_tick_generation == 0 {
+      self.push_pipe(rng);
     }
+
+    // sense and perceive environment
+    self.decision();
+    // make movements
+    self.movement(ticks);
+  }
+
+  // fly a whole episode with the population, handing it back evaluated; birds
+  // never meet, so with the parallel feature the population is split into
+  // chunks that fly copies of the episode on their own threads, its pipes
+  // drawn from one seed, and the result does not depend on the split
+  pub(crate) fn evaluate(&mut self, population: Vec<BirdIndividual>, rng: &mut impl RngCore) -> Vec<BirdIndividual> {
+    let birds: Vec<_> = self.individuals_as_birds(population.into_iter(), rng).collect();
+    let seed = rng.next_u64();
+
+    #[cfg(not(feature = "parallel"))]
+    let chunks = vec![birds];
+
+    #[cfg(feature = "parallel")]
+    let chunks: Vec<_> = {
+      let chunk_len = birds.len().div_ceil(rayon::current_num_threads()).max(1);
+      let mut birds = birds.into_iter();
+
+      iter::from_fn(|| Some(birds.by_ref().take(chunk_len).collect::<Vec<_>>()))
+        .take_while(|chunk| !chunk.is_empty())
+        .collect()
+    };
+
+    let episodes: Vec<_> = chunks
+      .into_iter()
+      .map(|birds| self.episode(birds))
+      .collect();
+
+    #[cfg(not(feature = "parallel"))]
+    let episodes = episodes.into_iter();
+
+    #[cfg(feature = "parallel")]
+    let episodes = episodes.into_par_iter();
+
+    // chunks are in population order, and come back that way
+    let evaluated: Vec<Vec<_>> = episodes
+      .map(|mut episode| {
+        episode.fly(&mut ChaCha8Rng::seed_from_u64(seed));
+        episode.birds_as_individuals().collect()
+      })
+      .collect();
+
+    self.reset();
+    evaluated.into_iter().flatten().collect()
+  }
+
+  fn fly(&mut self, rng: &mut impl RngCore) {
+    for ticks in 0..EPISODE_TICK_LIMIT {
+      if self.alive_birds.is_empty() {
+        break;
+      }
+
+      self.step(ticks, rng);
+    }
+  }
+
+  pub(crate) fn reset(&mut self) {
+    self.pipes.clear();
+    self.dead_birds.clear();
+    self.pipes_spawned = 0;
   }
 
   pub(crate) fn collision(&mut self) {
@@ -39,9 +164,10 @@ impl World {
     self.bird_decision(closest_pos);
   }
 
-  pub(crate) fn movement(&mut self) {
+  pub(crate) fn movement(&mut self, ticks: usize) {
     self.bird_movement(); // +/- y
-    self.pipe_movement(); // -x
+    self.pipe_movement(ticks); // -x
+    self.bird_score(); // pipes cleared, distance
   }
 
   pub(crate) fn birds_as_individuals(&mut self) -> impl Iterator<Item = BirdIndividual> + '_ {
@@ -49,21 +175,44 @@ impl World {
     let alive = mem::take(&mut self.alive_birds);
     let dead  = mem::take(&mut self.dead_birds);
 
-    alive
+    // keep population order, index based optimizers pair individuals across generations
+    let mut birds: Vec<_> = alive
       .into_iter()
       .chain(dead)
+      .collect();
+    birds.sort_by_key(|bird| bird.index);
+
+    birds
+      .into_iter()
       .map(Into::into)
   }
 
   pub(crate) fn individuals_as_birds<'p>(&self, population: impl Iterator<Item = BirdIndividual> +'p, rng: &'p mut impl RngCore) -> impl Iterator<Item = Bird> + 'p {
+    let config = self.config.clone();
+
     population
-      .map(|bi| bi.into_bird(rng))
+      .enumerate()
+      .map(move |(index, bi)| {
+        let mut bird = bi.into_bird(rng, &config);
+        bird.index = index;
+        bird
+      })
   }
 
   fn bird_collision(&mut self) {
     let closest_pipe = self.next_pipe();
+    let gap = closest_pipe
+      .as_ref()
+      .map(|p| p.position)
+      .unwrap_or(na::Point2::new(1.0, 0.5));
+
     let dead_birds = self.alive_birds
-      .extract_if(|bird| bird.collision(closest_pipe.as_ref())); // #![feature(extract_if)]
+      .extract_if(|bird| bird.collision(closest_pipe.as_ref(), &self.config)) // #![feature(extract_if)]
+      .map(|mut bird| {
+        bird.die(gap);
+        bird.score(self.fitness.as_ref());
+        bird
+      });
 
     self.dead_birds.extend(dead_birds);
   }
@@ -71,46 +220,63 @@ impl World {
   fn bird_decision(&mut self, closest_pos: na::Point2<f64>) {
     self.alive_birds
       .iter_mut()
-      .for_each(|bird| bird.decision(closest_pos));
+      .for_each(|bird| bird.decision(closest_pos, &self.config));
   }
 
   fn bird_movement(&mut self) {
     self.alive_birds
       .iter_mut()
-      .for_each(|bird| bird.movement());
+      .for_each(|bird| bird.movement(&self.config));
   }
 
   fn pipe_collision(&mut self) {
     self.pipes
-      .iter()
-      .for_each(|pipe| {
-        if pipe.collision() {
-          self.alive_birds
-            .iter_mut()
-            .for_each(|bird| {
-              bird.passes += 1;
-            });
-        }
-      });
+      .retain(|p| !p.collision(&self.config));
+  }
 
-    self.pipes
-      .retain(|p| !p.collision());
+  fn pipe_movement(&mut self, ticks: usize) {
+    // birds still alive clear a pipe once its trailing edge moves past them,
+    // the same edge `next_pipe` stops looking at it from
+    let edge = self.config.bird_x - self.config.pipe_offset_x;
+    let mut cleared = 0;
+
+    for pipe in &mut self.pipes {
+      let ahead = pipe.position.x > edge;
+      pipe.movement(&self.config);
+
+      if ahead && pipe.position.x <= edge {
+        cleared += 1;
+      }
+    }
+
+    for _ in 0..cleared {
+      self.alive_birds
+        .iter_mut()
+        .for_each(|bird| bird.pass(ticks));
+    }
   }
 
-  fn pipe_movement(&mut self) {
-    self.pipes
+  fn bird_score(&mut self) {
+    self.alive_birds
       .iter_mut()
-      .for_each(|pipe| pipe.movement());
+      .for_each(|bird| bird.score(self.fitness.as_ref()));
   }
 
   pub(crate) fn push_pipe(&mut self, rng: &mut impl RngCore) {
-    self.pipes.push_back(Pipe::random(rng));
+    // a designed course starts over once every gap was spawned
+    let pipe = match self.course.as_slice() {
+      [] => Pipe::random(rng, &self.config),
+      course => Pipe::with_gap(course[self.pipes_spawned % course.len()], &self.config),
+    };
+
+    self.pipes_spawned += 1;
+    self.pipes.push_back(pipe);
   }
 
   pub(crate) fn next_pipe(&self) -> Option<Pipe> {
     self.pipes
       .iter()
-      .filter(|pipe| pipe.position.x > BIRD_X - PIPE_OFFSET_X) // filter pipes past bird
+      .filter(|pipe| pipe.position.x > self.config.bird_x - self.config.pipe_offset_x) // filter pipes past bird
       .min_by(|a, b| {
         a.position.x.partial_cmp(&b.position.x).unwrap_or(std::cmp::Ordering::Equal)
       })
@@ -126,4 +292,8 @@ impl World {
   pub fn pipes(&self) -> impl Iterator<Item = &'_ Pipe> {
     self.pipes.iter()
   }
+
+  pub fn config(&self) -> &SimulationConfig {
+    &self.config
+  }
 }
\ No newline at end of file
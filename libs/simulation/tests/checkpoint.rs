@@ -0,0 +1,143 @@
+// a run resumed from a checkpoint has to carry on exactly like one that never stopped
+use lib_simulation as sim;
+use sim::ga;
+
+use std::env;
+
+type SpeciatedGeneticAlgorithm = ga::GeneticAlgorithm<
+  ga::SpeciatedSelection<ga::RouletteWheelSelection>,
+  ga::UniformCrossover,
+  ga::GaussianMutation,
+>;
+
+type NoveltyGeneticAlgorithm = ga::GeneticAlgorithm<
+  ga::NoveltySelection<ga::RouletteWheelSelection>,
+  ga::UniformCrossover,
+  ga::GaussianMutation,
+>;
+
+fn step_until<O: ga::Optimizer>(simulation: &mut sim::Simulation<O>, rng: &mut impl rand::RngCore, generations: usize) {
+  while simulation.generations() < generations {
+    simulation.step(rng);
+  }
+}
+
+fn fitnesses<O: ga::Optimizer>(simulation: &sim::Simulation<O>) -> Vec<f64> {
+  simulation
+    .history()
+    .iter()
+    .flat_map(|stats| [stats.min_fitness(), stats.avg_fitness(), stats.max_fitness()])
+    .collect()
+}
+
+// an uninterrupted run of 5 generations, and one stopped mid-episode after 3
+// and resumed from a checkpoint
+fn interrupt<O: ga::Optimizer>(name: &str, optimizer: impl Fn() -> O) -> (sim::Simulation<O>, sim::Simulation<O>) {
+  let (mut uninterrupted, mut rng) = sim::Simulation::seeded(11, optimizer());
+  step_until(&mut uninterrupted, &mut rng, 5);
+
+  let (mut interrupted, mut rng) = sim::Simulation::seeded(11, optimizer());
+  step_until(&mut interrupted, &mut rng, 3);
+  for _ in 0..20 {
+    interrupted.step(&mut rng);
+  }
+
+  let path = env::temp_dir().join(format!("checkpoint-{name}-{}.json", std::process::id()));
+  interrupted.save_checkpoint(&path, &rng).unwrap();
+  let (mut resumed, mut rng) = sim::Simulation::load_checkpoint(&path, optimizer()).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  step_until(&mut resumed, &mut rng, 5);
+  assert_eq!(fitnesses(&resumed), fitnesses(&uninterrupted));
+
+  (resumed, uninterrupted)
+}
+
+#[test]
+fn resumed_genetic_algorithm_matches_an_uninterrupted_run() {
+  interrupt("default", ga::DefaultGeneticAlgorithm::default);
+}
+
+#[test]
+fn resumed_speciated_genetic_algorithm_keeps_its_species() {
+  let speciated = || ga::GeneticAlgorithm::new(
+    ga::SpeciatedSelection::new(ga::RouletteWheelSelection::new(), 4.0, 2),
+    ga::UniformCrossover::new(),
+    ga::GaussianMutation::new(0.015, 0.3),
+  );
+  let (resumed, uninterrupted): (sim::Simulation<SpeciatedGeneticAlgorithm>, _) = interrupt("speciated", speciated);
+
+  let species = |simulation: &sim::Simulation<SpeciatedGeneticAlgorithm>| -> Vec<_> {
+    simulation.optimizer()
+      .species()
+      .iter()
+      .map(|species| (species.members().to_vec(), species.best_fitness(), species.stagnant_generations()))
+      .collect()
+  };
+  assert!(species(&uninterrupted).len() > 1);
+  assert_eq!(species(&resumed), species(&uninterrupted));
+}
+
+#[test]
+fn resumed_novelty_genetic_algorithm_keeps_its_archive() {
+  let novelty = || ga::GeneticAlgorithm::new(
+    ga::NoveltySelection::new(
+      ga::RouletteWheelSelection::new(),
+      ga::NoveltyObjective::Mixed { novelty_weight: 0.5 },
+      ga::NoveltyArchive::new(5, 0.05, 100),
+    ),
+    ga::UniformCrossover::new(),
+    ga::GaussianMutation::new(0.015, 0.3),
+  );
+  let (resumed, uninterrupted): (sim::Simulation<NoveltyGeneticAlgorithm>, _) = interrupt("novelty", novelty);
+
+  let archive = |simulation: &sim::Simulation<NoveltyGeneticAlgorithm>| -> Vec<Vec<f64>> {
+    simulation.optimizer()
+      .archive()
+      .behaviours()
+      .map(<[f64]>::to_vec)
+      .collect()
+  };
+  assert!(!archive(&uninterrupted).is_empty());
+  assert_eq!(archive(&resumed), archive(&uninterrupted));
+}
+
+#[test]
+fn resumed_cma_es_matches_an_uninterrupted_run() {
+  let (mut uninterrupted, mut rng) = sim::Simulation::seeded(11, ga::CmaEs::new(0.5));
+  step_until(&mut uninterrupted, &mut rng, 5);
+
+  // stop mid-episode, with the cma-es state well past its first generation
+  let (mut interrupted, mut rng) = sim::Simulation::seeded(11, ga::CmaEs::new(0.5));
+  step_until(&mut interrupted, &mut rng, 3);
+  for _ in 0..20 {
+    interrupted.step(&mut rng);
+  }
+
+  let path = env::temp_dir().join(format!("checkpoint-{}.json", std::process::id()));
+  interrupted.save_checkpoint(&path, &rng).unwrap();
+  let (mut resumed, mut rng) = sim::Simulation::load_checkpoint(&path, ga::CmaEs::new(0.5)).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  assert_eq!(resumed.optimizer().sigma(), interrupted.optimizer().sigma());
+  step_until(&mut resumed, &mut rng, 5);
+
+  assert_eq!(fitnesses(&resumed), fitnesses(&uninterrupted));
+  assert_eq!(resumed.optimizer().sigma(), uninterrupted.optimizer().sigma());
+  assert!(resumed.optimizer().mean().unwrap().iter().eq(uninterrupted.optimizer().mean().unwrap().iter()));
+}
+
+#[test]
+fn resumes_with_the_fitness_function_set_since() {
+  let (mut simulation, mut rng) = sim::Simulation::seeded(11, ga::CmaEs::new(0.5));
+  simulation.set_fitness_config(sim::FitnessConfig::PipesPassed).unwrap();
+  assert!(simulation.set_fitness_config(sim::FitnessConfig::FlapEconomy { flap_cost: -1.0 }).is_err());
+  simulation.step(&mut rng);
+
+  let path = env::temp_dir().join(format!("checkpoint-fitness-{}.json", std::process::id()));
+  simulation.save_checkpoint(&path, &rng).unwrap();
+  let (resumed, _) = sim::Simulation::load_checkpoint(&path, ga::CmaEs::new(0.5)).unwrap();
+  std::fs::remove_file(&path).unwrap();
+
+  assert_eq!(resumed.config().fitness, sim::FitnessConfig::PipesPassed);
+}
@@ -0,0 +1,62 @@
+// seeded runs have to replay bit for bit, natively and in wasm alike, for a
+// given feature set; the wasm build checks the same recorded fingerprint with
+// `wasm-pack test --node libs/simulation-wasm`
+use lib_simulation as sim;
+use sim::ga;
+
+// of `fingerprint(7, 5)`, only a deliberate change to the simulation may change
+// it; breeding in parallel draws from other streams, so it has its own, and
+// the wasm build, which breeds sequentially, checks the first
+#[cfg(not(feature = "parallel"))]
+const FINGERPRINT: u64 = 14_914_109_139_417_448_003;
+#[cfg(feature = "parallel")]
+const FINGERPRINT: u64 = 271_594_633_416_575_010;
+
+fn fingerprint(seed: u64, generations: usize) -> u64 {
+  let (mut simulation, mut rng) = sim::Simulation::from_seed(seed);
+  simulation.run(&mut rng, ga::MaxGenerations(generations));
+  simulation.fingerprint()
+}
+
+#[test]
+fn same_seed_replays_identically() {
+  assert_eq!(fingerprint(7, 5), fingerprint(7, 5));
+}
+
+#[test]
+fn different_seeds_diverge() {
+  assert_ne!(fingerprint(7, 5), fingerprint(8, 5));
+}
+
+#[test]
+fn matches_recorded_fingerprint() {
+  assert_eq!(fingerprint(7, 5), FINGERPRINT);
+}
+
+// the wasm build runs the optimizer built from the default config
+#[test]
+fn default_config_replays_like_the_default_genetic_algorithm() {
+  let optimizer = ga::OptimizerConfig::from(ga::GeneticAlgorithmConfig::default())
+    .build()
+    .expect("Error - default config is valid");
+  let (mut simulation, mut rng) = sim::Simulation::seeded(7, optimizer);
+  simulation.run(&mut rng, ga::MaxGenerations(5));
+
+  assert_eq!(simulation.fingerprint(), FINGERPRINT);
+}
+
+// episodes fly in one chunk per thread, and breeding streams by child, so
+// neither may depend on how many threads there are
+#[cfg(feature = "parallel")]
+#[test]
+fn thread_count_does_not_matter() {
+  let on = |threads| {
+    rayon::ThreadPoolBuilder::new()
+      .num_threads(threads)
+      .build()
+      .unwrap()
+      .install(|| fingerprint(7, 5))
+  };
+
+  assert_eq!(on(1), on(4));
+}
@@ -0,0 +1,24 @@
+// observers are `Send`, so a simulation can be trained on another thread
+use std::ops::ControlFlow;
+use std::thread;
+
+use lib_simulation as sim;
+use sim::ga;
+
+#[test]
+fn observed_simulation_trains_on_another_thread() {
+  let (mut simulation, mut rng) = sim::Simulation::seeded(3, ga::DynOptimizer::default());
+  simulation.observe(ga::OnGenerationEnd(|generation, _: &ga::Statistics| {
+    if generation == 1 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+  }));
+
+  let simulation = thread::spawn(move || {
+    simulation.run(&mut rng, ga::MaxGenerations(10));
+    simulation
+  })
+  .join()
+  .unwrap();
+
+  assert!(simulation.is_stopped());
+  assert_eq!(simulation.history().len(), 2);
+}
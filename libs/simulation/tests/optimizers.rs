@@ -0,0 +1,85 @@
+// every optimizer a config can pick has to train birds end to end
+use lib_simulation as sim;
+use sim::ga;
+
+fn train(config: impl Into<ga::OptimizerConfig>) -> sim::Simulation<ga::DynOptimizer> {
+  let (mut simulation, mut rng) = sim::Simulation::seeded(3, config.into().build().unwrap());
+  simulation.run(&mut rng, ga::MaxGenerations(3));
+  simulation
+}
+
+#[test]
+fn speciated_selection() {
+  let simulation = train(ga::GeneticAlgorithmConfig {
+    selection: ga::SelectionConfig::Speciated { compatibility_threshold: 3.0, stagnation_limit: 15 },
+    ..Default::default()
+  });
+
+  assert_eq!(simulation.history().len(), 3);
+}
+
+#[test]
+fn novelty_selection() {
+  let simulation = train(ga::GeneticAlgorithmConfig {
+    selection: ga::SelectionConfig::Novelty { k: 15, threshold: 0.5, capacity: 500, novelty_weight: 0.5 },
+    ..Default::default()
+  });
+
+  assert_eq!(simulation.history().len(), 3);
+}
+
+#[test]
+fn tournament_selection() {
+  let simulation = train(ga::GeneticAlgorithmConfig {
+    selection: ga::SelectionConfig::Tournament { size: 3 },
+    ..Default::default()
+  });
+
+  assert_eq!(simulation.history().len(), 3);
+}
+
+#[test]
+fn cma_es() {
+  let simulation = train(ga::OptimizerConfig::CmaEs { sigma: 0.5 });
+
+  assert_eq!(simulation.history().len(), 3);
+}
+
+#[test]
+fn differential_evolution() {
+  let simulation = train(ga::OptimizerConfig::DifferentialEvolution {
+    variant: ga::DifferentialVariant::Rand1Bin,
+    weight: 0.8,
+    crossover_rate: 0.9,
+  });
+
+  assert_eq!(simulation.history().len(), 3);
+}
+
+#[test]
+fn evolution_strategy() {
+  let simulation = train(ga::OptimizerConfig::EvolutionStrategy {
+    mu: 10,
+    replacement: ga::Replacement::Plus,
+    mutation: ga::MutationConfig::Normal { chance: 0.1, sigma: 0.3 },
+  });
+
+  assert_eq!(simulation.history().len(), 3);
+}
+
+#[test]
+fn invalid_optimizer_config() {
+  assert!(ga::OptimizerConfig::CmaEs { sigma: 0.0 }.build().is_err());
+}
+
+#[test]
+fn nsga2() {
+  let simulation = train(ga::OptimizerConfig::Nsga2 {
+    crossover: ga::CrossoverConfig::Uniform,
+    mutation: ga::MutationConfig::Gaussian { chance: 0.05, mag: 0.3 },
+  });
+
+  assert_eq!(simulation.history().len(), 3);
+  assert!(!simulation.pareto_front().is_empty());
+  assert!(simulation.pareto_front().iter().all(|objectives| objectives.len() == 3));
+}